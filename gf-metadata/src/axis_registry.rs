@@ -0,0 +1,494 @@
+//! The axis registry: what axes like wght and opsz mean and their named positions.
+
+use std::str::FromStr;
+
+use crate::{AxisLocation, AxisProto, AxisSegmentProto, FallbackProto, FamilyProto, GoogleFonts};
+
+/// The value of a named position on axis, e.g. Bold on wght, matched ignoring case against
+/// both the name and display name of each fallback.
+pub fn named_position(axis: &AxisProto, name: &str) -> Option<f32> {
+    axis.fallback
+        .iter()
+        .find(|f| {
+            f.name().eq_ignore_ascii_case(name) || f.display_name().eq_ignore_ascii_case(name)
+        })
+        .map(|f| f.value())
+}
+
+/// An axis of a family along with its definition in the axis registry, see
+/// [`GoogleFonts::family_axes`]
+#[derive(Clone, Debug, PartialEq)]
+pub struct FamilyAxis<'a> {
+    /// The range the family supports
+    pub segment: &'a AxisSegmentProto,
+    /// None if the registry doesn't know the tag
+    pub definition: Option<&'a AxisProto>,
+    /// The family's registry_default_overrides entry or failing that the registry default,
+    /// clamped to the range the family supports.
+    pub default: f32,
+}
+
+impl FamilyAxis<'_> {
+    pub fn tag(&self) -> &str {
+        self.segment.tag()
+    }
+
+    pub fn min(&self) -> f32 {
+        self.segment.min_value()
+    }
+
+    pub fn max(&self) -> f32 {
+        self.segment.max_value()
+    }
+
+    /// A label for the axis, e.g. for a slider, the display name from the registry if possible
+    pub fn label(&self) -> &str {
+        self.definition
+            .map(|d| d.display_name())
+            .filter(|n| !n.is_empty())
+            .unwrap_or(self.tag())
+    }
+
+    /// The named positions from the registry that the family supports, in order of value
+    ///
+    /// Typically where a renderer should sample the axis.
+    pub fn named_positions(&self) -> Vec<&FallbackProto> {
+        let mut positions = self
+            .definition
+            .map(|d| d.fallback.iter().collect::<Vec<_>>())
+            .unwrap_or_default();
+        positions.retain(|f| (self.min()..=self.max()).contains(&f.value()));
+        positions.sort_by(|a, b| a.value().total_cmp(&b.value()));
+        positions
+    }
+}
+
+pub(crate) fn family_axes<'a>(gf: &'a GoogleFonts, family: &'a FamilyProto) -> Vec<FamilyAxis<'a>> {
+    family
+        .axes
+        .iter()
+        .map(|segment| {
+            let definition = gf.axis(segment.tag());
+            let default = family
+                .registry_default_overrides
+                .get(segment.tag())
+                .copied()
+                .or_else(|| {
+                    definition
+                        .filter(|d| d.has_default_value())
+                        .map(|d| d.default_value())
+                })
+                .unwrap_or(segment.min_value())
+                // not clamp, which panics if metadata has min > max
+                .max(segment.min_value())
+                .min(segment.max_value());
+            FamilyAxis {
+                segment,
+                definition,
+                default,
+            }
+        })
+        .collect()
+}
+
+/// Values to sample an axis at, see [`GridAxis`]
+/// The most values a range may produce, so a tiny step can't exhaust memory
+const MAX_RANGE_VALUES: usize = 1000;
+
+/// How many steps a range takes after start, one less than the number of values it has
+fn range_steps(start: f32, end: f32, step: f32) -> f32 {
+    // Allow a little slop so floating point error doesn't drop end
+    ((end - start) / step + 1e-3).floor()
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum GridValue {
+    Value(f32),
+    /// The minimum the family supports
+    Min,
+    /// The family's default, see [`FamilyAxis::default`]
+    Default,
+    /// The maximum the family supports
+    Max,
+    /// Every named position from the registry the family supports
+    Named,
+    /// start, start + step, ... up to and including end
+    Range {
+        start: f32,
+        end: f32,
+        step: f32,
+    },
+}
+
+impl FromStr for GridValue {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let number = |v: &str| {
+            v.trim()
+                .parse::<f32>()
+                .ok()
+                .filter(|v| v.is_finite())
+                .ok_or_else(|| format!("Invalid axis value {v:?}"))
+        };
+        match s.trim() {
+            "min" => Ok(GridValue::Min),
+            "default" => Ok(GridValue::Default),
+            "max" => Ok(GridValue::Max),
+            "named" => Ok(GridValue::Named),
+            s => match s.split_once("..") {
+                Some((start, rest)) => {
+                    let Some((end, step)) = rest.split_once(':') else {
+                        return Err(format!("Invalid range {s:?}, expected start..end:step"));
+                    };
+                    let (start, end, step) = (number(start)?, number(end)?, number(step)?);
+                    if step <= 0.0 || end < start {
+                        return Err(format!(
+                            "Invalid range {s:?}, step must be positive and end at least start"
+                        ));
+                    }
+                    if range_steps(start, end, step) >= MAX_RANGE_VALUES as f32 {
+                        return Err(format!(
+                            "Invalid range {s:?}, it has more than {MAX_RANGE_VALUES} values"
+                        ));
+                    }
+                    Ok(GridValue::Range { start, end, step })
+                }
+                None => number(s).map(GridValue::Value),
+            },
+        }
+    }
+}
+
+impl GridValue {
+    fn resolve(&self, axis: &FamilyAxis) -> Vec<f32> {
+        match self {
+            GridValue::Value(v) => vec![*v],
+            GridValue::Min => vec![axis.min()],
+            GridValue::Default => vec![axis.default],
+            GridValue::Max => vec![axis.max()],
+            GridValue::Named => axis.named_positions().iter().map(|f| f.value()).collect(),
+            GridValue::Range { start, end, step } => {
+                // Multiply rather than accumulate so we don't drift off the end
+                let steps = range_steps(*start, *end, *step) as usize;
+                (0..=steps).map(|i| start + i as f32 * step).collect()
+            }
+        }
+    }
+}
+
+/// The values to sample one axis at, parses from TAG=VALUE[,VALUE...]
+///
+/// Each value is a number, min, default, max, named or a range start..end:step, e.g.
+/// wght=100..900:100 or wdth=min,max.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GridAxis {
+    pub tag: String,
+    pub values: Vec<GridValue>,
+}
+
+impl FromStr for GridAxis {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some((tag, values)) = s.split_once('=') else {
+            return Err(format!(
+                "Invalid grid axis {s:?}, expected TAG=VALUE[,VALUE...]"
+            ));
+        };
+        let tag = tag.trim();
+        if tag.is_empty() || tag.len() > 4 {
+            return Err(format!("Invalid axis tag {tag:?}"));
+        }
+        Ok(GridAxis {
+            tag: tag.to_string(),
+            values: values
+                .split(',')
+                .map(GridValue::from_str)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+/// Locations to sample a family at, every combination of the values of each axis
+///
+/// Values are resolved against the axes of a family, see [`GoogleFonts::family_axes`], so the
+/// same grid can be applied to every family.
+///
+/// ```
+/// use gf_metadata::AxisGrid;
+///
+/// // wght 100, 200, ... 900 at both the narrowest and widest wdth
+/// let grid = AxisGrid::new([
+///     "wght=100..900:100".parse().unwrap(),
+///     "wdth=min,max".parse().unwrap(),
+/// ]);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AxisGrid(Vec<GridAxis>);
+
+impl AxisGrid {
+    pub fn new(axes: impl IntoIterator<Item = GridAxis>) -> Self {
+        Self(axes.into_iter().collect())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// The locations to sample a family with these axes at
+    ///
+    /// Values outside the range the family supports are dropped, as are axes the family
+    /// doesn't have. If nothing is left that's just the default location.
+    pub fn locations(&self, axes: &[FamilyAxis]) -> Vec<AxisLocation> {
+        let mut locations = vec![Vec::new()];
+        for grid_axis in self.0.iter() {
+            let Some(axis) = axes.iter().find(|a| a.tag() == grid_axis.tag) else {
+                continue;
+            };
+            let mut values = Vec::new();
+            for value in grid_axis.values.iter().flat_map(|v| v.resolve(axis)) {
+                if (axis.min()..=axis.max()).contains(&value) && !values.contains(&value) {
+                    values.push(value);
+                }
+            }
+            if values.is_empty() {
+                continue;
+            }
+            locations = locations
+                .into_iter()
+                .flat_map(|loc: Vec<(String, f32)>| {
+                    values.iter().map(move |v| {
+                        let mut loc = loc.clone();
+                        loc.push((grid_axis.tag.clone(), *v));
+                        loc
+                    })
+                })
+                .collect();
+        }
+        locations.into_iter().map(AxisLocation::new).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::{
+        AXIS_REGISTRY_DATA_DIR, iter_axes, read_family,
+        test_util::{fake_repo, temp_dir, testdata_file_content},
+    };
+
+    #[test]
+    fn axis_registry() {
+        let root = temp_dir("axis_registry");
+        fake_repo(&root);
+        let registry = root.join(AXIS_REGISTRY_DATA_DIR);
+        fs::create_dir_all(&registry).unwrap();
+        fs::write(
+            registry.join("weight.textproto"),
+            r#"# Weight
+tag: "wght"
+display_name: "Weight"
+min_value: 1
+default_value: 400
+max_value: 1000
+precision: 0
+fallback {
+  name: "Thin"
+  value: 100
+}
+fallback {
+  name: "Regular"
+  value: 400
+}
+fallback {
+  name: "Bold"
+  value: 700
+}
+fallback {
+  name: "ExtraBlack"
+  value: 1000
+}
+fallback_only: false
+description: "Adjust the style from lighter to bolder in typographic color."
+"#,
+        )
+        .unwrap();
+        fs::write(
+            registry.join("width.textproto"),
+            r#"tag: "wdth"
+display_name: "Width"
+min_value: 25
+default_value: 100
+max_value: 200
+precision: -1
+fallback {
+  name: "Condensed"
+  value: 75
+}
+fallback {
+  name: "Normal"
+  value: 100
+}
+"#,
+        )
+        .unwrap();
+        fs::write(registry.join("broken.textproto"), "tag: 42\n").unwrap();
+        // Only the data directory is read
+        fs::write(registry.join("../axes.textproto"), "tag: \"XXXX\"\n").unwrap();
+        let gf = GoogleFonts::new(root.clone(), None);
+
+        assert_eq!(3, gf.axes().len());
+        assert_eq!(3, iter_axes(&registry).count());
+        assert_eq!(1, gf.axes().iter().filter(|a| a.is_err()).count());
+        let wght = gf.axis("wght").unwrap();
+        assert_eq!(
+            ("Weight", 1.0, 400.0, 1000.0, 0),
+            (
+                wght.display_name(),
+                wght.min_value(),
+                wght.default_value(),
+                wght.max_value(),
+                wght.precision()
+            )
+        );
+        assert_eq!(Some(700.0), named_position(wght, "bold"));
+        assert_eq!(None, named_position(wght, "Heavy"));
+        assert!(gf.axis("opsz").is_none());
+
+        // Axes survive a trip through the cache
+        let cache_file = root.join("metadata.cache");
+        let cached = GoogleFonts::new(root.clone(), None).with_cache(cache_file.clone());
+        cached.axes();
+        cached.save_cache().unwrap();
+        let cached = GoogleFonts::new(root.clone(), None).with_cache(cache_file);
+        assert_eq!(Some(wght), cached.axis("wght"));
+
+        let mut roboto = read_family(&testdata_file_content("roboto-metadata.pb")).unwrap();
+        roboto
+            .registry_default_overrides
+            .insert("wdth".to_string(), 87.5);
+        let mut xopq = AxisSegmentProto::new();
+        xopq.set_tag("XOPQ".to_string());
+        xopq.set_min_value(27.0);
+        xopq.set_max_value(175.0);
+        roboto.axes.push(xopq);
+
+        let axes = gf
+            .family_axes(&roboto)
+            .into_iter()
+            .map(|a| {
+                (
+                    a.label().to_string(),
+                    a.min(),
+                    a.default,
+                    a.max(),
+                    a.named_positions()
+                        .into_iter()
+                        .map(|f| f.name().to_string())
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                (
+                    "Width".to_string(),
+                    75.0,
+                    87.5,
+                    100.0,
+                    vec!["Condensed".to_string(), "Normal".to_string()]
+                ),
+                (
+                    "Weight".to_string(),
+                    100.0,
+                    400.0,
+                    900.0,
+                    vec![
+                        "Thin".to_string(),
+                        "Regular".to_string(),
+                        "Bold".to_string()
+                    ]
+                ),
+                ("XOPQ".to_string(), 27.0, 27.0, 175.0, Vec::new()),
+            ],
+            axes
+        );
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn axis_grid() {
+        let root = temp_dir("axis_grid");
+        fake_repo(&root);
+        let gf = GoogleFonts::new(root.clone(), None);
+        let mut roboto = read_family(&testdata_file_content("roboto-metadata.pb")).unwrap();
+        roboto
+            .registry_default_overrides
+            .insert("wdth".to_string(), 100.0);
+        let axes = gf.family_axes(&roboto);
+
+        let locations = |grid: &[&str]| {
+            AxisGrid::new(grid.iter().map(|a| a.parse::<GridAxis>().unwrap()))
+                .locations(&axes)
+                .into_iter()
+                .map(|l| l.to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(vec![""], locations(&[]));
+        // Axes Roboto lacks and values outside its range are dropped
+        assert_eq!(
+            vec![
+                "wght,wdth@100,75",
+                "wght,wdth@100,100",
+                "wght,wdth@500,75",
+                "wght,wdth@500,100",
+                "wght,wdth@900,75",
+                "wght,wdth@900,100",
+            ],
+            locations(&["wght=100..900:400", "opsz=12", "wdth=min,50,default,max"])
+        );
+        assert_eq!(
+            vec!["wght@300", "wght@400", "wght@500"],
+            locations(&["wght=300..500.5:100,400"])
+        );
+        assert_eq!(vec![""], locations(&["wght=1000"]));
+
+        for bad in [
+            "wght",
+            "=100",
+            "wght=",
+            "wght=heavy",
+            "wght=900..100:100",
+            "wght=1..9",
+        ] {
+            assert!(bad.parse::<GridAxis>().is_err(), "{bad}");
+        }
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn grid_range_limit() {
+        let Ok(GridAxis { values, .. }) = "wght=1..1000:1".parse::<GridAxis>() else {
+            panic!("1000 values should be allowed");
+        };
+        let segment = AxisSegmentProto::new();
+        let axis = FamilyAxis {
+            segment: &segment,
+            definition: None,
+            default: 1.0,
+        };
+        assert_eq!(1000, values[0].resolve(&axis).len());
+        for bad in ["wght=0..1000:1", "wght=100..900:1e-9", "wght=0..1:1e-45"] {
+            assert_eq!(
+                Err(format!(
+                    "Invalid range {:?}, it has more than 1000 values",
+                    &bad[5..]
+                )),
+                bad.parse::<GridAxis>()
+            );
+        }
+    }
+}
@@ -0,0 +1,441 @@
+//! A binary cache of parsed metadata, so repeat runs needn't re-parse the whole repository.
+//!
+//! Entries are keyed by source file and are only used if the file's modification time and
+//! size are unchanged. Anything wrong with the cache file, even total corruption, just means
+//! we parse from source again.
+
+use std::{
+    collections::HashMap,
+    env, fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Mutex,
+    time::UNIX_EPOCH,
+};
+
+use protobuf::{CodedInputStream, CodedOutputStream, Message};
+
+use crate::{
+    AxisLocation, AxisProto, FamilyProto, GfMetadataError, LanguageProto, RegionProto, ScriptProto,
+    Tag, TagMetadata,
+};
+
+/// The default cache file, $XDG_CACHE_HOME/gf-metadata/metadata.cache or
+/// ~/.cache/gf-metadata/metadata.cache
+pub fn default_cache_file() -> Option<PathBuf> {
+    env::var_os("XDG_CACHE_HOME")
+        .filter(|d| !d.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| home::home_dir().map(|h| h.join(".cache")))
+        .map(|d| d.join("gf-metadata/metadata.cache"))
+}
+
+const MAGIC: &[u8] = b"gf-metadata cache\n";
+
+/// Changes whenever the layout changes. The crate version is also checked, protos may change.
+const FORMAT: u32 = 1;
+
+/// The kinds of thing we cache
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub(crate) enum Section {
+    Families = 1,
+    Languages = 2,
+    Regions = 3,
+    Scripts = 4,
+    Tags = 5,
+    TagMetadata = 6,
+    Axes = 7,
+}
+
+impl Section {
+    fn from_u32(v: u32) -> Option<Self> {
+        [
+            Section::Families,
+            Section::Languages,
+            Section::Regions,
+            Section::Scripts,
+            Section::Tags,
+            Section::TagMetadata,
+            Section::Axes,
+        ]
+        .into_iter()
+        .find(|s| *s as u32 == v)
+    }
+}
+
+/// Identifies a version of a source file
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+struct Stamp {
+    modified_secs: u64,
+    modified_nanos: u32,
+    len: u64,
+}
+
+impl Stamp {
+    fn of(path: &Path) -> Option<Self> {
+        let metadata = fs::metadata(path).ok()?;
+        let modified = metadata.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+        Some(Stamp {
+            modified_secs: modified.as_secs(),
+            modified_nanos: modified.subsec_nanos(),
+            len: metadata.len(),
+        })
+    }
+}
+
+struct Entry {
+    stamp: Stamp,
+    data: Vec<u8>,
+}
+
+/// Something that can be stored in the cache
+pub(crate) trait Cacheable: Sized {
+    fn to_bytes(&self) -> Option<Vec<u8>>;
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self>;
+}
+
+fn message_to_bytes(message: &impl Message) -> Option<Vec<u8>> {
+    message.write_to_bytes().ok()
+}
+
+fn message_from_bytes<M: Message>(bytes: &[u8]) -> Option<M> {
+    M::parse_from_bytes(bytes).ok()
+}
+
+impl Cacheable for FamilyProto {
+    fn to_bytes(&self) -> Option<Vec<u8>> {
+        message_to_bytes(self)
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        message_from_bytes(bytes)
+    }
+}
+
+impl Cacheable for LanguageProto {
+    fn to_bytes(&self) -> Option<Vec<u8>> {
+        message_to_bytes(self)
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        message_from_bytes(bytes)
+    }
+}
+
+impl Cacheable for RegionProto {
+    fn to_bytes(&self) -> Option<Vec<u8>> {
+        message_to_bytes(self)
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        message_from_bytes(bytes)
+    }
+}
+
+impl Cacheable for ScriptProto {
+    fn to_bytes(&self) -> Option<Vec<u8>> {
+        message_to_bytes(self)
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        message_from_bytes(bytes)
+    }
+}
+
+impl Cacheable for AxisProto {
+    fn to_bytes(&self) -> Option<Vec<u8>> {
+        message_to_bytes(self)
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        message_from_bytes(bytes)
+    }
+}
+
+/// Write items as a count followed by each item in turn
+fn write_seq<T>(
+    items: &[T],
+    write: impl Fn(&mut CodedOutputStream, &T) -> protobuf::Result<()>,
+) -> Option<Vec<u8>> {
+    let mut bytes = Vec::new();
+    let mut os = CodedOutputStream::vec(&mut bytes);
+    os.write_uint64_no_tag(items.len() as u64).ok()?;
+    for item in items {
+        write(&mut os, item).ok()?;
+    }
+    os.flush().ok()?;
+    drop(os);
+    Some(bytes)
+}
+
+fn read_seq<T>(
+    bytes: &[u8],
+    read: impl Fn(&mut CodedInputStream) -> protobuf::Result<Option<T>>,
+) -> Option<Vec<T>> {
+    let mut is = CodedInputStream::from_bytes(bytes);
+    let len = is.read_uint64().ok()?;
+    (0..len).map(|_| read(&mut is).ok().flatten()).collect()
+}
+
+impl Cacheable for Vec<Tag> {
+    fn to_bytes(&self) -> Option<Vec<u8>> {
+        write_seq(self, |os, tag| {
+            os.write_string_no_tag(&tag.family)?;
+            os.write_string_no_tag(&tag.loc.to_string())?;
+            os.write_string_no_tag(&tag.tag)?;
+            os.write_float_no_tag(tag.value)
+        })
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        read_seq(bytes, |is| {
+            let family = is.read_string()?;
+            let loc = is.read_string()?;
+            let tag = is.read_string()?;
+            let value = is.read_float()?;
+            Ok(AxisLocation::from_str(&loc).ok().map(|loc| Tag {
+                family,
+                loc,
+                tag,
+                value,
+            }))
+        })
+    }
+}
+
+impl Cacheable for Vec<TagMetadata> {
+    fn to_bytes(&self) -> Option<Vec<u8>> {
+        write_seq(self, |os, metadata| {
+            os.write_string_no_tag(&metadata.tag)?;
+            os.write_float_no_tag(metadata.min_value)?;
+            os.write_float_no_tag(metadata.max_value)?;
+            os.write_string_no_tag(&metadata.prompt_name)
+        })
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        read_seq(bytes, |is| {
+            Ok(Some(TagMetadata {
+                tag: is.read_string()?,
+                min_value: is.read_float()?,
+                max_value: is.read_float()?,
+                prompt_name: is.read_string()?,
+            }))
+        })
+    }
+}
+
+type Entries = HashMap<(Section, PathBuf), Entry>;
+
+/// Parsed metadata, persisted between runs
+pub(crate) struct MetadataCache {
+    file: PathBuf,
+    repo_dir: PathBuf,
+    /// What we loaded from file
+    previous: Entries,
+    /// What we've loaded from source, or confirmed is still valid, this run
+    current: Mutex<Entries>,
+}
+
+fn version() -> String {
+    format!("{FORMAT}/{}", env!("CARGO_PKG_VERSION"))
+}
+
+fn read_entries(bytes: &[u8], repo_dir: &Path) -> protobuf::Result<Option<Entries>> {
+    if !bytes.starts_with(MAGIC) {
+        return Ok(None);
+    }
+    let mut is = CodedInputStream::from_bytes(&bytes[MAGIC.len()..]);
+    if is.read_string()? != version() || Path::new(&is.read_string()?) != repo_dir {
+        return Ok(None);
+    }
+    let mut entries = HashMap::new();
+    for _ in 0..is.read_uint64()? {
+        let section = is.read_uint32()?;
+        let path = PathBuf::from(is.read_string()?);
+        let stamp = Stamp {
+            modified_secs: is.read_uint64()?,
+            modified_nanos: is.read_uint32()?,
+            len: is.read_uint64()?,
+        };
+        let data = is.read_bytes()?;
+        let Some(section) = Section::from_u32(section) else {
+            return Ok(None);
+        };
+        entries.insert((section, path), Entry { stamp, data });
+    }
+    Ok(Some(entries))
+}
+
+impl MetadataCache {
+    /// Load the cache for repo_dir from file. A missing or unusable file yields an empty cache.
+    pub(crate) fn load(file: PathBuf, repo_dir: &Path) -> Self {
+        let previous = fs::read(&file)
+            .ok()
+            .and_then(|bytes| read_entries(&bytes, repo_dir).ok().flatten())
+            .unwrap_or_default();
+        MetadataCache {
+            file,
+            repo_dir: repo_dir.to_path_buf(),
+            previous,
+            current: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The cached value for path if it's still valid, else the result of load(path)
+    ///
+    /// Only successful loads are cached.
+    pub(crate) fn get_or_load<T: Cacheable>(
+        &self,
+        section: Section,
+        path: &Path,
+        load: impl FnOnce(&Path) -> Result<T, GfMetadataError>,
+    ) -> Result<T, GfMetadataError> {
+        // Stamp before loading, if the file changes while we read it we'll reload next time
+        let Some(stamp) = Stamp::of(path) else {
+            return load(path);
+        };
+        let key = (section, path.to_path_buf());
+        let cached = self
+            .previous
+            .get(&key)
+            .filter(|e| e.stamp == stamp)
+            .and_then(|e| T::from_bytes(&e.data).map(|value| (value, Some(e.data.clone()))));
+        let (value, data) = match cached {
+            Some(cached) => cached,
+            None => {
+                let value = load(path)?;
+                let data = value.to_bytes();
+                (value, data)
+            }
+        };
+        if let Some(data) = data {
+            self.current
+                .lock()
+                .unwrap()
+                .insert(key, Entry { stamp, data });
+        }
+        Ok(value)
+    }
+
+    /// Write everything loaded this run, plus anything cached earlier that still exists
+    pub(crate) fn save(&self) -> Result<(), GfMetadataError> {
+        let current = self.current.lock().unwrap();
+        let mut entries = current.iter().collect::<Vec<_>>();
+        entries.extend(
+            self.previous
+                .iter()
+                .filter(|(k, _)| !current.contains_key(k) && k.1.exists()),
+        );
+
+        let mut bytes = MAGIC.to_vec();
+        let mut os = CodedOutputStream::vec(&mut bytes);
+        let write = |os: &mut CodedOutputStream| -> protobuf::Result<()> {
+            os.write_string_no_tag(&version())?;
+            os.write_string_no_tag(&self.repo_dir.to_string_lossy())?;
+            let entries = entries
+                .iter()
+                .filter_map(|((s, p), e)| p.to_str().map(|p| (s, p, e)))
+                .collect::<Vec<_>>();
+            os.write_uint64_no_tag(entries.len() as u64)?;
+            for (section, path, entry) in entries {
+                os.write_uint32_no_tag(*section as u32)?;
+                os.write_string_no_tag(path)?;
+                os.write_uint64_no_tag(entry.stamp.modified_secs)?;
+                os.write_uint32_no_tag(entry.stamp.modified_nanos)?;
+                os.write_uint64_no_tag(entry.stamp.len)?;
+                os.write_bytes_no_tag(&entry.data)?;
+            }
+            os.flush()
+        };
+        write(&mut os).map_err(|e| GfMetadataError::io(&self.file, std::io::Error::other(e)))?;
+        drop(os);
+
+        // Write then rename so a reader never sees a partial cache
+        if let Some(parent) = self.file.parent() {
+            fs::create_dir_all(parent).map_err(|e| GfMetadataError::io(parent, e))?;
+        }
+        let tmp = self.file.with_extension("tmp");
+        fs::write(&tmp, &bytes).map_err(|e| GfMetadataError::io(&tmp, e))?;
+        fs::rename(&tmp, &self.file).map_err(|e| GfMetadataError::io(&self.file, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        GoogleFonts,
+        test_util::{fake_repo, temp_dir, testdata_file_content},
+    };
+
+    #[test]
+    fn metadata_cache() {
+        let root = temp_dir("metadata_cache");
+        fake_repo(&root);
+        let metadata_file = root.join("ofl/roboto/METADATA.pb");
+        let metadata = testdata_file_content("roboto-metadata.pb");
+        fs::create_dir_all(metadata_file.parent().unwrap()).unwrap();
+        fs::write(&metadata_file, &metadata).unwrap();
+        fs::create_dir_all(root.join("tags/all")).unwrap();
+        fs::write(
+            root.join("tags/all/quality.csv"),
+            "Roboto,/Quality/Drawing,90\n",
+        )
+        .unwrap();
+        let cache_file = root.join("cache/metadata.cache");
+
+        let load = |cache: bool| {
+            let gf = GoogleFonts::new(root.clone(), None);
+            if cache {
+                gf.with_cache(cache_file.clone())
+            } else {
+                gf
+            }
+        };
+        let gf = load(true);
+        assert_eq!("Roboto", gf.families()[0].1.as_ref().unwrap().name());
+        assert_eq!(1, gf.tags().unwrap().len());
+        gf.save_cache().unwrap();
+
+        // Garbage of the same size and modification time isn't noticed, proving we used the cache
+        let modified = fs::metadata(&metadata_file).unwrap().modified().unwrap();
+        let set_modified = |t| {
+            fs::File::options()
+                .write(true)
+                .open(&metadata_file)
+                .unwrap()
+                .set_modified(t)
+                .unwrap()
+        };
+        fs::write(&metadata_file, "x".repeat(metadata.len())).unwrap();
+        set_modified(modified);
+        let gf = load(true);
+        assert_eq!("Roboto", gf.families()[0].1.as_ref().unwrap().name());
+        assert_eq!(
+            "/Quality/Drawing",
+            gf.tag_index()
+                .unwrap()
+                .tags_for_family("Roboto")
+                .next()
+                .unwrap()
+                .tag
+        );
+        assert!(load(false).families()[0].1.is_err());
+
+        // A new modification time is
+        set_modified(modified + std::time::Duration::from_secs(1));
+        assert!(load(true).families()[0].1.is_err());
+
+        // A corrupt cache is ignored
+        fs::write(&metadata_file, &metadata).unwrap();
+        fs::write(&cache_file, "garbage").unwrap();
+        let gf = load(true);
+        assert!(gf.families()[0].1.is_ok());
+        gf.save_cache().unwrap();
+        assert!(load(true).families()[0].1.is_ok());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
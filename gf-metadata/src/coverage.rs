@@ -0,0 +1,157 @@
+use std::collections::HashSet;
+
+use crate::{ExemplarCharsProto, LanguageProto};
+
+/// How well a font supports a language
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum LanguageSupport {
+    /// Every base and mark exemplar character is present
+    Supported,
+    /// Some, but not all, base and mark exemplar characters are present
+    Partial,
+    /// None of the base exemplar characters are present
+    Missing,
+}
+
+/// Exemplar characters a font lacks, by exemplar category
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MissingChars {
+    pub base: Vec<char>,
+    pub marks: Vec<char>,
+    pub auxiliary: Vec<char>,
+    pub numerals: Vec<char>,
+    pub punctuation: Vec<char>,
+}
+
+impl MissingChars {
+    pub fn is_empty(&self) -> bool {
+        self.iter().next().is_none()
+    }
+
+    /// Every missing char, base first
+    pub fn iter(&self) -> impl Iterator<Item = char> + '_ {
+        self.base
+            .iter()
+            .chain(self.marks.iter())
+            .chain(self.auxiliary.iter())
+            .chain(self.numerals.iter())
+            .chain(self.punctuation.iter())
+            .copied()
+    }
+}
+
+/// The support a font offers a single language
+#[derive(Clone, Debug, PartialEq)]
+pub struct LanguageCoverage<'a> {
+    pub language: &'a LanguageProto,
+    pub support: LanguageSupport,
+    pub missing: MissingChars,
+}
+
+/// The unique characters of a gflanguages exemplar string, in order of first appearance
+///
+/// Exemplars are space separated and may contain {multi char sequences} and the dotted
+/// circle placeholder for marks, ◌. Neither the braces nor the placeholder are required.
+pub fn exemplar_chars(exemplar: &str) -> Vec<char> {
+    let mut seen = HashSet::new();
+    exemplar
+        .chars()
+        .filter(|c| !c.is_whitespace() && !matches!(c, '{' | '}' | '\u{25CC}'))
+        .filter(|c| seen.insert(*c))
+        .collect()
+}
+
+fn missing(exemplar: &str, has_char: &impl Fn(char) -> bool) -> Vec<char> {
+    exemplar_chars(exemplar)
+        .into_iter()
+        .filter(|c| !has_char(*c))
+        .collect()
+}
+
+/// Check a language's exemplar characters against a font.
+///
+/// has_char reports whether the font maps a char, typically by consulting its cmap.
+/// Support is judged by the base and mark exemplars; the auxiliary, numeral and punctuation
+/// exemplars are checked and reported as missing but don't affect the verdict. Returns None
+/// if the language has no base exemplar characters so support can't be judged.
+pub fn language_coverage<'a>(
+    language: &'a LanguageProto,
+    has_char: impl Fn(char) -> bool,
+) -> Option<LanguageCoverage<'a>> {
+    let exemplars: &ExemplarCharsProto = &language.exemplar_chars;
+    let base = exemplar_chars(exemplars.base());
+    if base.is_empty() {
+        return None;
+    }
+
+    let missing = MissingChars {
+        base: missing(exemplars.base(), &has_char),
+        marks: missing(exemplars.marks(), &has_char),
+        auxiliary: missing(exemplars.auxiliary(), &has_char),
+        numerals: missing(exemplars.numerals(), &has_char),
+        punctuation: missing(exemplars.punctuation(), &has_char),
+    };
+    let support = if missing.base.len() == base.len() {
+        LanguageSupport::Missing
+    } else if missing.base.is_empty() && missing.marks.is_empty() {
+        LanguageSupport::Supported
+    } else {
+        LanguageSupport::Partial
+    };
+    Some(LanguageCoverage {
+        language,
+        support,
+        missing,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::read_language;
+
+    #[test]
+    fn exemplar_chars_ignore_braces_and_placeholders() {
+        assert_eq!(
+            vec!['a', 'b', 'c', 'h', '\u{301}'],
+            exemplar_chars("a b {ch} ◌́ a")
+        );
+    }
+
+    #[test]
+    fn coverage_verdicts() {
+        let lang = read_language(
+            r#"
+            id: "xx_Latn"
+            exemplar_chars {
+              base: "a b c é"
+              marks: "◌́"
+              numerals: "0 1 ٣"
+            }
+            "#,
+        )
+        .unwrap();
+        let supported = language_coverage(&lang, |c| c != '٣').unwrap();
+        let partial = language_coverage(&lang, |c| c.is_ascii()).unwrap();
+        let missing = language_coverage(&lang, |c| c.is_ascii_digit()).unwrap();
+        assert_eq!(
+            (
+                LanguageSupport::Supported,
+                LanguageSupport::Partial,
+                LanguageSupport::Missing
+            ),
+            (supported.support, partial.support, missing.support)
+        );
+        assert_eq!(vec!['٣'], supported.missing.iter().collect::<Vec<_>>());
+        assert_eq!(
+            vec!['é', '\u{301}', '٣'],
+            partial.missing.iter().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn no_coverage_without_exemplars() {
+        let lang = read_language("id: \"xx_Latn\"").unwrap();
+        assert_eq!(None, language_coverage(&lang, |_| true));
+    }
+}
@@ -0,0 +1,338 @@
+//! CSS @font-face rules for a family and its metric-compatible fallbacks.
+
+use std::fmt::Write;
+
+use crate::{
+    AxisSegmentProto, FamilyFallbackProto, FamilyProto, FontProto, TargetTypeProto, is_variable,
+};
+
+/// Generates @font-face CSS for a family
+///
+/// A face is written for each font, variable fonts only if the family has any, with ranges
+/// for weight, width and slant taken from the family's axes. A face named per
+/// [`fallback_family_name`] is written for each fallback, using local fonts adjusted to match
+/// the metrics of the family so swapping in the web font doesn't shift layout.
+///
+/// ```
+/// use gf_metadata::{FontFaceCss, TargetTypeProto};
+///
+/// // Fonts served from /fonts, with fallbacks tuned for Windows
+/// let css = FontFaceCss::new()
+///     .url_prefix("/fonts/")
+///     .target(TargetTypeProto::TARGET_OS_WINDOWS);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct FontFaceCss {
+    url_prefix: String,
+    font_display: Option<String>,
+    target: Option<TargetTypeProto>,
+}
+
+impl Default for FontFaceCss {
+    fn default() -> Self {
+        Self {
+            url_prefix: String::new(),
+            font_display: Some("swap".to_string()),
+            target: None,
+        }
+    }
+}
+
+/// The name we give the fallback faces of a family
+pub fn fallback_family_name(family: &FamilyProto) -> String {
+    format!("{} Fallback", family.name())
+}
+
+/// A font-family value that uses family, then its fallback faces, then generic
+pub fn font_stack(family: &FamilyProto, generic: &str) -> String {
+    format!(
+        "{}, {}, {generic}",
+        quote(family.name()),
+        quote(&fallback_family_name(family))
+    )
+}
+
+fn quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn axis<'a>(axes: &'a [AxisSegmentProto], tag: &str) -> Option<&'a AxisSegmentProto> {
+    axes.iter().find(|a| a.tag() == tag)
+}
+
+/// A descriptor value that is either a single value or a range
+fn range(min: f32, max: f32, unit: &str) -> String {
+    if min == max {
+        format!("{min}{unit}")
+    } else {
+        format!("{min}{unit} {max}{unit}")
+    }
+}
+
+fn format_hint(filename: &str) -> &str {
+    match filename.rsplit('.').next() {
+        Some("woff2") => "woff2",
+        Some("woff") => "woff",
+        Some("otf") => "opentype",
+        _ => "truetype",
+    }
+}
+
+impl FontFaceCss {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Prepended to font filenames to form src urls, e.g. https://example.com/fonts/
+    pub fn url_prefix(mut self, url_prefix: &str) -> Self {
+        self.url_prefix = url_prefix.to_string();
+        self
+    }
+
+    /// The font-display descriptor of web font faces, swap by default. Empty to omit.
+    pub fn font_display(mut self, font_display: &str) -> Self {
+        self.font_display = (!font_display.is_empty()).then(|| font_display.to_string());
+        self
+    }
+
+    /// Only write fallbacks for this platform, and those that don't specify one
+    pub fn target(mut self, target: TargetTypeProto) -> Self {
+        self.target = Some(target);
+        self
+    }
+
+    fn wants(&self, fallback: &FamilyFallbackProto) -> bool {
+        let Some(target) = self.target else {
+            return true;
+        };
+        fallback.target.is_empty() || fallback.target.iter().any(|t| t.target_type() == target)
+    }
+
+    /// The fonts we write faces for, variable fonts if there are any, otherwise all of them
+    fn fonts<'a>(&self, family: &'a FamilyProto) -> Vec<&'a FontProto> {
+        let variable = family
+            .fonts
+            .iter()
+            .filter(|f| is_variable(family, f))
+            .collect::<Vec<_>>();
+        if variable.is_empty() {
+            family.fonts.iter().collect()
+        } else {
+            variable
+        }
+    }
+
+    fn write_font(&self, css: &mut String, family: &FamilyProto, font: &FontProto) {
+        let axes = if is_variable(family, font) {
+            family.axes.as_slice()
+        } else {
+            &[]
+        };
+        css.push_str("@font-face {\n");
+        let _ = writeln!(css, "  font-family: {};", quote(family.name()));
+        match axis(axes, "slnt") {
+            // slnt is counter-clockwise, oblique angles are clockwise
+            Some(slnt) if font.style() != "italic" => {
+                let _ = writeln!(
+                    css,
+                    "  font-style: oblique {};",
+                    range(-slnt.max_value(), -slnt.min_value(), "deg")
+                );
+            }
+            _ => {
+                let _ = writeln!(css, "  font-style: {};", font.style());
+            }
+        }
+        let weight = match axis(axes, "wght") {
+            Some(wght) => range(wght.min_value(), wght.max_value(), ""),
+            None => font.weight().to_string(),
+        };
+        let _ = writeln!(css, "  font-weight: {weight};");
+        if let Some(wdth) = axis(axes, "wdth") {
+            let _ = writeln!(
+                css,
+                "  font-stretch: {};",
+                range(wdth.min_value(), wdth.max_value(), "%")
+            );
+        }
+        if let Some(font_display) = &self.font_display {
+            let _ = writeln!(css, "  font-display: {font_display};");
+        }
+        let _ = writeln!(
+            css,
+            "  src: url({}) format(\"{}\");",
+            quote(&format!("{}{}", self.url_prefix, font.filename())),
+            format_hint(font.filename())
+        );
+        css.push_str("}\n");
+    }
+
+    fn write_fallback(
+        &self,
+        css: &mut String,
+        family: &FamilyProto,
+        fallback: &FamilyFallbackProto,
+    ) {
+        css.push_str("@font-face {\n");
+        let _ = writeln!(
+            css,
+            "  font-family: {};",
+            quote(&fallback_family_name(family))
+        );
+        if let Some(ital) = axis(&fallback.axis_target, "ital") {
+            let style = if ital.min_value() >= 1.0 {
+                "italic"
+            } else {
+                "normal"
+            };
+            let _ = writeln!(css, "  font-style: {style};");
+        }
+        if let Some(wght) = axis(&fallback.axis_target, "wght") {
+            let _ = writeln!(
+                css,
+                "  font-weight: {};",
+                range(wght.min_value(), wght.max_value(), "")
+            );
+        }
+        if let Some(wdth) = axis(&fallback.axis_target, "wdth") {
+            let _ = writeln!(
+                css,
+                "  font-stretch: {};",
+                range(wdth.min_value(), wdth.max_value(), "%")
+            );
+        }
+        if fallback.has_size_adjust_pct() {
+            let _ = writeln!(css, "  size-adjust: {}%;", fallback.size_adjust_pct());
+        }
+        if fallback.has_ascent_override_pct() {
+            let _ = writeln!(
+                css,
+                "  ascent-override: {}%;",
+                fallback.ascent_override_pct()
+            );
+        }
+        let src = fallback
+            .local_src
+            .iter()
+            .map(|s| format!("local({})", quote(s)))
+            .collect::<Vec<_>>();
+        let _ = writeln!(css, "  src: {};", src.join(", "));
+        css.push_str("}\n");
+    }
+
+    /// @font-face rules for family's fonts followed by those for its fallbacks
+    pub fn write(&self, family: &FamilyProto) -> String {
+        let mut css = String::new();
+        for font in self.fonts(family) {
+            self.write_font(&mut css, family, font);
+        }
+        for fallback in family
+            .fallbacks
+            .iter()
+            .filter(|f| !f.local_src.is_empty() && self.wants(f))
+        {
+            self.write_fallback(&mut css, family, fallback);
+        }
+        css
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{read_family, test_util::testdata_file_content};
+
+    #[test]
+    fn ranges() {
+        assert_eq!("400", range(400.0, 400.0, ""));
+        assert_eq!("75% 100%", range(75.0, 100.0, "%"));
+        assert_eq!("0deg 9.5deg", range(0.0, 9.5, "deg"));
+    }
+
+    #[test]
+    fn names_are_quoted() {
+        assert_eq!(r#""Noto \"Sans\" \\o/""#, quote(r#"Noto "Sans" \o/"#));
+    }
+
+    #[test]
+    fn format_hints() {
+        assert_eq!("truetype", format_hint("Roboto[wdth,wght].ttf"));
+        assert_eq!("opentype", format_hint("Foo-Regular.otf"));
+        assert_eq!("woff2", format_hint("Foo-Regular.woff2"));
+    }
+
+    #[test]
+    fn font_face_css() {
+        let roboto = read_family(&format!(
+            "{}{}",
+            testdata_file_content("roboto-metadata.pb"),
+            r#"
+fallbacks {
+  axis_target { tag: "wght" min_value: 400 max_value: 400 }
+  target { target_type: TARGET_OS_WINDOWS }
+  size_adjust_pct: 100.3
+  ascent_override_pct: 92.7
+  local_src: "Arial"
+}
+fallbacks {
+  axis_target { tag: "wght" min_value: 700 max_value: 700 }
+  target { target_type: TARGET_OS_MAC }
+  size_adjust_pct: 98.5
+  local_src: "Helvetica Neue"
+  local_src: "Helvetica"
+}
+"#
+        ))
+        .unwrap();
+
+        let css = FontFaceCss::new()
+            .url_prefix("/fonts/")
+            .target(TargetTypeProto::TARGET_OS_WINDOWS)
+            .write(&roboto);
+        assert_eq!(
+            r#"@font-face {
+  font-family: "Roboto";
+  font-style: normal;
+  font-weight: 100 900;
+  font-stretch: 75% 100%;
+  font-display: swap;
+  src: url("/fonts/Roboto[wdth,wght].ttf") format("truetype");
+}
+@font-face {
+  font-family: "Roboto";
+  font-style: italic;
+  font-weight: 100 900;
+  font-stretch: 75% 100%;
+  font-display: swap;
+  src: url("/fonts/Roboto-Italic[wdth,wght].ttf") format("truetype");
+}
+@font-face {
+  font-family: "Roboto Fallback";
+  font-weight: 400;
+  size-adjust: 100.3%;
+  ascent-override: 92.7%;
+  src: local("Arial");
+}
+"#,
+            css
+        );
+
+        // Without a target every fallback is written
+        let css = FontFaceCss::new().font_display("").write(&roboto);
+        assert!(!css.contains("font-display"), "{css}");
+        assert!(
+            css.contains(r#"src: local("Helvetica Neue"), local("Helvetica");"#),
+            "{css}"
+        );
+        assert_eq!(
+            r#""Roboto", "Roboto Fallback", sans-serif"#,
+            font_stack(&roboto, "sans-serif")
+        );
+
+        // Static families get a face per font at its own weight
+        let kosugi = read_family(&testdata_file_content("kosugimaru-metadata.pb")).unwrap();
+        let css = FontFaceCss::new().write(&kosugi);
+        assert_eq!(kosugi.fonts.len(), css.matches("@font-face").count());
+        assert!(css.contains("  font-weight: 400;\n"), "{css}");
+    }
+}
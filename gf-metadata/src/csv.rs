@@ -0,0 +1,264 @@
+//! A small RFC 4180 csv reader and writer for the google/fonts tags files.
+//!
+//! Deviates from the RFC only in being lenient about whitespace around fields, which the
+//! tags files use freely, e.g. `Georama, "ital,wght@1,100", /quant/stroke_width_min, 16.97`.
+
+use crate::GfMetadataError;
+
+/// A csv record, the 1-based line it started on and its fields
+pub(crate) type Record = (usize, Vec<String>);
+
+#[derive(Copy, Clone, PartialEq)]
+enum State {
+    /// At the start of a field, skipping leading whitespace
+    StartField,
+    /// In an unquoted field
+    Unquoted,
+    /// In a quoted field
+    Quoted,
+    /// Just saw a quote in a quoted field, either the end of the field or the first half of ""
+    QuoteInQuoted,
+    /// After the closing quote of a quoted field, skipping trailing whitespace
+    AfterQuoted,
+}
+
+fn error(line: usize, message: &str) -> GfMetadataError {
+    GfMetadataError::csv(message).with_line(line)
+}
+
+/// Parse csv text into records. Blank lines are skipped.
+///
+/// Quoted fields may contain commas, newlines and escaped quotes ("").
+pub(crate) fn parse_records(content: &str) -> Result<Vec<Record>, GfMetadataError> {
+    let mut records = Vec::new();
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut state = State::StartField;
+    let mut line = 1;
+    let mut record_line = 1;
+
+    let mut chars = content.chars().peekable();
+    while let Some(c) = chars.next() {
+        // Treat \r\n as \n
+        let c = if c == '\r' && chars.peek() == Some(&'\n') {
+            chars.next().unwrap()
+        } else {
+            c
+        };
+        let is_newline = c == '\n';
+
+        match (state, c) {
+            (State::Quoted, '"') => state = State::QuoteInQuoted,
+            (State::Quoted, c) => field.push(c),
+            (State::QuoteInQuoted, '"') => {
+                field.push('"');
+                state = State::Quoted;
+            }
+            (State::StartField, '"') => state = State::Quoted,
+            (State::Unquoted, '"') => {
+                return Err(error(line, "Unexpected quote in unquoted field"));
+            }
+            (_, ',') => {
+                fields.push(finish_field(&mut field, state));
+                state = State::StartField;
+            }
+            (_, '\n') => {
+                if state != State::StartField || !fields.is_empty() {
+                    fields.push(finish_field(&mut field, state));
+                }
+                if !fields.is_empty() {
+                    records.push((record_line, std::mem::take(&mut fields)));
+                }
+                state = State::StartField;
+            }
+            (State::StartField | State::AfterQuoted, c) if c.is_whitespace() => (),
+            (State::QuoteInQuoted, c) if c.is_whitespace() => state = State::AfterQuoted,
+            (State::QuoteInQuoted | State::AfterQuoted, c) => {
+                return Err(error(
+                    line,
+                    &format!("Unexpected {c:?} after closing quote"),
+                ));
+            }
+            (State::StartField | State::Unquoted, c) => {
+                field.push(c);
+                state = State::Unquoted;
+            }
+        }
+
+        if is_newline {
+            line += 1;
+            if state == State::StartField && fields.is_empty() {
+                record_line = line;
+            }
+        }
+    }
+
+    if state == State::Quoted {
+        return Err(error(record_line, "Unterminated quoted field"));
+    }
+    if state != State::StartField || !fields.is_empty() {
+        fields.push(finish_field(&mut field, state));
+        records.push((record_line, fields));
+    }
+    Ok(records)
+}
+
+fn finish_field(field: &mut String, state: State) -> String {
+    let value = std::mem::take(field);
+    if state == State::Unquoted {
+        value.trim_end().to_string()
+    } else {
+        value
+    }
+}
+
+/// Parse a single csv record, e.g. one line of a tags file
+pub(crate) fn parse_record(s: &str) -> Result<Vec<String>, GfMetadataError> {
+    let mut records = parse_records(s)?;
+    match records.len() {
+        0 => Err(GfMetadataError::csv("Empty record")),
+        1 => Ok(records.pop().unwrap().1),
+        _ => Err(GfMetadataError::csv("Expected a single record")),
+    }
+}
+
+/// A type that can be read from, and written to, a csv record
+pub(crate) trait CsvRecord: Sized {
+    fn from_record(fields: &[String]) -> Result<Self, GfMetadataError>;
+
+    fn to_record(&self) -> Vec<String>;
+
+    /// Whether fields look like a header, e.g. names where values are expected
+    fn is_header(fields: &[String]) -> bool;
+}
+
+/// Parse csv text into T, skipping the first record if it looks like a header
+pub(crate) fn parse_csv<T: CsvRecord>(content: &str) -> Result<Vec<T>, GfMetadataError> {
+    let records = parse_records(content)?;
+    let skip = records
+        .first()
+        .map(|(_, fields)| T::is_header(fields))
+        .unwrap_or_default();
+    records
+        .into_iter()
+        .skip(usize::from(skip))
+        .map(|(line, fields)| T::from_record(&fields).map_err(|e| e.with_line(line)))
+        .collect()
+}
+
+/// Quote field if necessary for [`parse_records`] to read it back unchanged
+fn write_field(field: &str) -> String {
+    let needs_quotes = field.contains([',', '"', '\n', '\r'])
+        || field.starts_with(char::is_whitespace)
+        || field.ends_with(char::is_whitespace);
+    if needs_quotes {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Write a single csv record, without a line ending
+pub(crate) fn write_record(fields: &[impl AsRef<str>]) -> String {
+    fields
+        .iter()
+        .map(|f| write_field(f.as_ref()))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Write items as csv text, one record per line, optionally preceded by a header
+pub(crate) fn write_csv<T: CsvRecord>(header: Option<&[String]>, items: &[T]) -> String {
+    let mut csv = String::new();
+    for record in header
+        .map(|h| h.to_vec())
+        .into_iter()
+        .chain(items.iter().map(T::to_record))
+    {
+        csv.push_str(&write_record(&record));
+        csv.push('\n');
+    }
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields(s: &str) -> Vec<String> {
+        parse_record(s).unwrap()
+    }
+
+    #[test]
+    fn unquoted() {
+        assert_eq!(vec!["a", "b c", "1"], fields("a, b c ,1"));
+    }
+
+    #[test]
+    fn quoted_with_comma() {
+        assert_eq!(
+            vec![
+                "Georama",
+                "ital,wght@1,100",
+                "/quant/stroke_width_min",
+                "16.97"
+            ],
+            fields("Georama, \"ital,wght@1,100\", /quant/stroke_width_min, 16.97")
+        );
+    }
+
+    #[test]
+    fn escaped_quotes() {
+        assert_eq!(vec!["say \"hi\"", ""], fields("\"say \"\"hi\"\"\",\"\""));
+    }
+
+    #[test]
+    fn empty_fields() {
+        assert_eq!(vec!["", "t", ""], fields(",t,"));
+    }
+
+    #[test]
+    fn multiline_records() {
+        let records = parse_records("a,b\r\n\n\"multi\nline\",c\nd,e").unwrap();
+        assert_eq!(
+            vec![
+                (1, vec!["a".to_string(), "b".to_string()]),
+                (3, vec!["multi\nline".to_string(), "c".to_string()]),
+                (5, vec!["d".to_string(), "e".to_string()]),
+            ],
+            records
+        );
+    }
+
+    #[test]
+    fn unterminated_quote() {
+        let Err(GfMetadataError::Csv { line, .. }) = parse_records("a,b\n\"c,d\ne,f\n") else {
+            panic!("Should fail");
+        };
+        assert_eq!(Some(2), line);
+    }
+
+    #[test]
+    fn junk_after_quote() {
+        assert!(parse_record("\"a\"b,c").is_err());
+        assert!(parse_record("a\"b,c").is_err());
+    }
+
+    #[test]
+    fn write_round_trips() {
+        let fields = vec![
+            "plain".to_string(),
+            "ital,wght@1,100".to_string(),
+            "say \"hi\"".to_string(),
+            " padded ".to_string(),
+            "multi\nline".to_string(),
+            "".to_string(),
+        ];
+        let record = write_record(&fields);
+        assert_eq!(
+            "plain,\"ital,wght@1,100\",\"say \"\"hi\"\"\",\" padded \",\"multi\nline\",",
+            record
+        );
+        assert_eq!(fields, parse_record(&record).unwrap());
+    }
+}
@@ -0,0 +1,439 @@
+//! What changed between two snapshots of the google/fonts repository.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+};
+
+use crate::{
+    AxisLocation, AxisSegmentProto, ExemplarPolicy, FamilyProto, GoogleFonts, select_exemplar,
+};
+
+/// One change to a family present in both snapshots
+#[derive(Clone, Debug, PartialEq)]
+pub enum Change {
+    /// A font file was added to METADATA.pb
+    FontAdded(String),
+    /// A font file was removed from METADATA.pb
+    FontRemoved(String),
+    /// A font file was replaced by one with a different name but the same style and weight
+    FontRenamed { from: String, to: String },
+    /// The font we render to represent the family is different
+    ExemplarChanged {
+        from: Option<String>,
+        to: Option<String>,
+    },
+    AxesChanged {
+        from: Vec<AxisSegmentProto>,
+        to: Vec<AxisSegmentProto>,
+    },
+    /// The family's own sample text, not that of its primary language, changed
+    SampleTextChanged,
+    PrimaryLanguageChanged {
+        from: Option<String>,
+        to: Option<String>,
+    },
+    PrimaryScriptChanged {
+        from: Option<String>,
+        to: Option<String>,
+    },
+    /// A tag value was added (from is None), removed (to is None) or changed
+    TagChanged {
+        tag: String,
+        loc: AxisLocation,
+        from: Option<f32>,
+        to: Option<f32>,
+    },
+}
+
+impl Change {
+    /// Whether this change could alter the test image rendered for the family
+    pub fn affects_image(&self) -> bool {
+        matches!(
+            self,
+            Change::ExemplarChanged { .. }
+                | Change::AxesChanged { .. }
+                | Change::SampleTextChanged
+                | Change::PrimaryLanguageChanged { .. }
+                | Change::PrimaryScriptChanged { .. }
+        )
+    }
+
+    /// Whether this change could alter the embedding of the family
+    ///
+    /// Embeddings are computed from the test image and the tags.
+    pub fn affects_embedding(&self) -> bool {
+        self.affects_image() || matches!(self, Change::TagChanged { .. })
+    }
+}
+
+fn or_none(value: &Option<impl fmt::Display>) -> String {
+    value
+        .as_ref()
+        .map(|v| v.to_string())
+        .unwrap_or_else(|| "none".to_string())
+}
+
+fn axes(axes: &[AxisSegmentProto]) -> String {
+    if axes.is_empty() {
+        return "none".to_string();
+    }
+    axes.iter()
+        .map(|a| format!("{} {}..{}", a.tag(), a.min_value(), a.max_value()))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Change::FontAdded(file) => write!(f, "font added {file}"),
+            Change::FontRemoved(file) => write!(f, "font removed {file}"),
+            Change::FontRenamed { from, to } => write!(f, "font renamed {from} => {to}"),
+            Change::ExemplarChanged { from, to } => {
+                write!(f, "exemplar {} => {}", or_none(from), or_none(to))
+            }
+            Change::AxesChanged { from, to } => write!(f, "axes {} => {}", axes(from), axes(to)),
+            Change::SampleTextChanged => write!(f, "sample text changed"),
+            Change::PrimaryLanguageChanged { from, to } => {
+                write!(f, "primary language {} => {}", or_none(from), or_none(to))
+            }
+            Change::PrimaryScriptChanged { from, to } => {
+                write!(f, "primary script {} => {}", or_none(from), or_none(to))
+            }
+            Change::TagChanged { tag, loc, from, to } => {
+                write!(f, "tag {tag}")?;
+                if !loc.is_default() {
+                    write!(f, " at {loc}")?;
+                }
+                write!(f, " {} => {}", or_none(from), or_none(to))
+            }
+        }
+    }
+}
+
+/// A family that was added, removed or changed
+#[derive(Clone, Debug, PartialEq)]
+pub struct FamilyDiff {
+    pub name: String,
+    /// The filename of the family's exemplar font, from the newer snapshot if it has the family
+    ///
+    /// Chosen by the policy passed to [`GoogleFonts::changes_since`].
+    pub exemplar: Option<String>,
+    /// Always empty for added and removed families
+    pub changes: Vec<Change>,
+}
+
+/// What changed between two snapshots of the repository, see [`GoogleFonts::changes_since`]
+///
+/// Families are matched by name so a renamed family is reported as removed and added.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SnapshotDiff {
+    pub added: Vec<FamilyDiff>,
+    pub removed: Vec<FamilyDiff>,
+    pub changed: Vec<FamilyDiff>,
+}
+
+impl SnapshotDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+
+    /// The exemplar font files of added families and of families with changes that
+    /// [`Change::affects_image`]; their test images need to be rendered.
+    pub fn stale_images(&self) -> Vec<&str> {
+        self.added
+            .iter()
+            .chain(
+                self.changed
+                    .iter()
+                    .filter(|f| f.changes.iter().any(Change::affects_image)),
+            )
+            .filter_map(|f| f.exemplar.as_deref())
+            .collect()
+    }
+
+    /// The names of added families and of families with changes that
+    /// [`Change::affects_embedding`]; their embeddings need to be computed.
+    pub fn stale_embeddings(&self) -> Vec<&str> {
+        self.added
+            .iter()
+            .chain(
+                self.changed
+                    .iter()
+                    .filter(|f| f.changes.iter().any(Change::affects_embedding)),
+            )
+            .map(|f| f.name.as_str())
+            .collect()
+    }
+}
+
+impl fmt::Display for SnapshotDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for family in self.added.iter() {
+            writeln!(f, "+ {}", family.name)?;
+        }
+        for family in self.removed.iter() {
+            writeln!(f, "- {}", family.name)?;
+        }
+        for family in self.changed.iter() {
+            writeln!(f, "~ {}", family.name)?;
+            for change in family.changes.iter() {
+                writeln!(f, "    {change}")?;
+            }
+        }
+        write!(
+            f,
+            "{} added, {} removed, {} changed families",
+            self.added.len(),
+            self.removed.len(),
+            self.changed.len()
+        )
+    }
+}
+
+/// The successfully loaded families of gf by name, in the order they were loaded
+fn families_by_name(gf: &GoogleFonts) -> Vec<(&str, &FamilyProto)> {
+    let mut seen = HashSet::new();
+    gf.families()
+        .iter()
+        .filter_map(|(_, f)| f.as_ref().ok())
+        .filter(|f| seen.insert(f.name()))
+        .map(|f| (f.name(), f))
+        .collect()
+}
+
+/// (tag, location) => value for every tag of every family. Tags that fail to load are treated as
+/// absent.
+fn tags_by_family(gf: &GoogleFonts) -> HashMap<&str, HashMap<(&str, String), f32>> {
+    let mut tags: HashMap<_, HashMap<_, _>> = HashMap::new();
+    for tag in gf.tags().unwrap_or_default() {
+        tags.entry(tag.family.as_str())
+            .or_default()
+            .insert((tag.tag.as_str(), tag.loc.to_string()), tag.value);
+    }
+    tags
+}
+
+fn exemplar_file(family: &FamilyProto, policy: &dyn ExemplarPolicy) -> Option<String> {
+    select_exemplar(family, policy).map(|f| f.filename().to_string())
+}
+
+fn changed<T: PartialEq>(from: T, to: T) -> Option<(T, T)> {
+    (from != to).then_some((from, to))
+}
+
+fn font_changes(from: &FamilyProto, to: &FamilyProto) -> Vec<Change> {
+    let mut removed = from
+        .fonts
+        .iter()
+        .filter(|f| !to.fonts.iter().any(|t| t.filename() == f.filename()))
+        .collect::<Vec<_>>();
+    let mut added = to
+        .fonts
+        .iter()
+        .filter(|t| !from.fonts.iter().any(|f| f.filename() == t.filename()))
+        .collect::<Vec<_>>();
+
+    let mut changes = Vec::new();
+    removed.retain(|r| {
+        let Some(i) = added
+            .iter()
+            .position(|a| a.style() == r.style() && a.weight() == r.weight())
+        else {
+            return true;
+        };
+        changes.push(Change::FontRenamed {
+            from: r.filename().to_string(),
+            to: added.remove(i).filename().to_string(),
+        });
+        false
+    });
+    changes.extend(
+        removed
+            .into_iter()
+            .map(|f| Change::FontRemoved(f.filename().to_string())),
+    );
+    changes.extend(
+        added
+            .into_iter()
+            .map(|f| Change::FontAdded(f.filename().to_string())),
+    );
+    changes
+}
+
+fn family_changes(
+    from: &FamilyProto,
+    to: &FamilyProto,
+    from_tags: Option<&HashMap<(&str, String), f32>>,
+    to_tags: Option<&HashMap<(&str, String), f32>>,
+    policy: &dyn ExemplarPolicy,
+) -> Vec<Change> {
+    let mut changes = font_changes(from, to);
+    if let Some((from, to)) = changed(exemplar_file(from, policy), exemplar_file(to, policy)) {
+        changes.push(Change::ExemplarChanged { from, to });
+    }
+    if let Some((from, to)) = changed(&from.axes, &to.axes) {
+        changes.push(Change::AxesChanged {
+            from: from.clone(),
+            to: to.clone(),
+        });
+    }
+    if from.sample_text != to.sample_text {
+        changes.push(Change::SampleTextChanged);
+    }
+    if let Some((from, to)) = changed(&from.primary_language, &to.primary_language) {
+        changes.push(Change::PrimaryLanguageChanged {
+            from: from.clone(),
+            to: to.clone(),
+        });
+    }
+    if let Some((from, to)) = changed(&from.primary_script, &to.primary_script) {
+        changes.push(Change::PrimaryScriptChanged {
+            from: from.clone(),
+            to: to.clone(),
+        });
+    }
+
+    let empty = HashMap::new();
+    let (from_tags, to_tags) = (from_tags.unwrap_or(&empty), to_tags.unwrap_or(&empty));
+    let mut keys = from_tags.keys().chain(to_tags.keys()).collect::<Vec<_>>();
+    keys.sort();
+    keys.dedup();
+    for key in keys {
+        let (from, to) = (from_tags.get(key).copied(), to_tags.get(key).copied());
+        if from != to {
+            let (tag, loc) = key;
+            changes.push(Change::TagChanged {
+                tag: tag.to_string(),
+                loc: loc.parse().unwrap_or_default(),
+                from,
+                to,
+            });
+        }
+    }
+    changes
+}
+
+pub(crate) fn diff(
+    before: &GoogleFonts,
+    after: &GoogleFonts,
+    policy: &dyn ExemplarPolicy,
+) -> SnapshotDiff {
+    let (from_families, to_families) = (families_by_name(before), families_by_name(after));
+    let (from_tags, to_tags) = (tags_by_family(before), tags_by_family(after));
+    let from_by_name = from_families.iter().copied().collect::<HashMap<_, _>>();
+    let to_by_name = to_families.iter().copied().collect::<HashMap<_, _>>();
+
+    let summary = |family: &FamilyProto| FamilyDiff {
+        name: family.name().to_string(),
+        exemplar: exemplar_file(family, policy),
+        changes: Vec::new(),
+    };
+    let mut diff = SnapshotDiff::default();
+    for (name, to) in to_families.iter() {
+        let Some(from) = from_by_name.get(name) else {
+            diff.added.push(summary(to));
+            continue;
+        };
+        let changes = family_changes(from, to, from_tags.get(name), to_tags.get(name), policy);
+        if !changes.is_empty() {
+            diff.changed.push(FamilyDiff {
+                changes,
+                ..summary(to)
+            });
+        }
+    }
+    diff.removed = from_families
+        .iter()
+        .filter(|(name, _)| !to_by_name.contains_key(name))
+        .map(|(_, from)| summary(from))
+        .collect();
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::Path};
+
+    use super::*;
+    use crate::{
+        DefaultPolicy, read_family,
+        test_util::{fake_repo, temp_dir, testdata_file_content},
+        write_family,
+    };
+
+    fn write_snapshot(root: &Path, families: &[(&str, &FamilyProto)], tags: &str) -> GoogleFonts {
+        fake_repo(root);
+        for (dir, family) in families {
+            let dir = root.join("ofl").join(dir);
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(dir.join("METADATA.pb"), write_family(family)).unwrap();
+        }
+        fs::create_dir_all(root.join("tags/all")).unwrap();
+        fs::write(root.join("tags/all/families.csv"), tags).unwrap();
+        GoogleFonts::new(root.to_path_buf(), None)
+    }
+
+    #[test]
+    fn snapshot_diff() {
+        let root = temp_dir("snapshot_diff");
+        let roboto = read_family(&testdata_file_content("roboto-metadata.pb")).unwrap();
+        let wix = read_family(&testdata_file_content("wixmadefortext-metadata.pb")).unwrap();
+        let kosugi = read_family(&testdata_file_content("kosugimaru-metadata.pb")).unwrap();
+        let before = write_snapshot(
+            &root.join("before"),
+            &[("roboto", &roboto), ("wixmadefortext", &wix)],
+            "Roboto,/Quality/Drawing,90\nRoboto,wght@700,/Expressive/Loud,60\n",
+        );
+
+        let mut new_roboto = roboto.clone();
+        new_roboto.fonts[0].set_filename("Roboto[opsz,wdth,wght].ttf".to_string());
+        new_roboto.fonts.pop();
+        new_roboto.set_primary_script("Latn".to_string());
+        let after = write_snapshot(
+            &root.join("after"),
+            &[("roboto", &new_roboto), ("kosugimaru", &kosugi)],
+            "Roboto,/Quality/Drawing,80\nRoboto,/Expressive/Calm,20\n",
+        );
+
+        let diff = after.changes_since(&before, &DefaultPolicy::default());
+        assert_eq!(
+            vec!["Kosugi Maru"],
+            diff.added.iter().map(|f| &f.name).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec!["Wix Madefor Text"],
+            diff.removed.iter().map(|f| &f.name).collect::<Vec<_>>()
+        );
+        assert_eq!(1, diff.changed.len());
+        assert_eq!(
+            vec![
+                "font renamed Roboto[wdth,wght].ttf => Roboto[opsz,wdth,wght].ttf",
+                "font removed Roboto-Italic[wdth,wght].ttf",
+                "exemplar Roboto[wdth,wght].ttf => Roboto[opsz,wdth,wght].ttf",
+                "primary script none => Latn",
+                "tag /Expressive/Calm none => 20",
+                "tag /Expressive/Loud at wght@700 60 => none",
+                "tag /Quality/Drawing 90 => 80",
+            ],
+            diff.changed[0]
+                .changes
+                .iter()
+                .map(|c| c.to_string())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec!["KosugiMaru-Regular.ttf", "Roboto[opsz,wdth,wght].ttf"],
+            diff.stale_images()
+        );
+        assert_eq!(vec!["Kosugi Maru", "Roboto"], diff.stale_embeddings());
+        assert!(
+            before
+                .changes_since(&before, &DefaultPolicy::default())
+                .is_empty()
+        );
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
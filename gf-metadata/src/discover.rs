@@ -0,0 +1,192 @@
+//! Locate a local copy of the google/fonts repository.
+
+use std::{
+    env,
+    ffi::OsString,
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+use crate::GfMetadataError;
+
+/// Environment variable that may hold the path to a google/fonts checkout
+pub const REPO_DIR_ENV_VAR: &str = "GOOGLE_FONTS_DIR";
+
+/// Directories every google/fonts checkout has at its root
+const REPO_MARKERS: &[&str] = &["ofl", "apache", "tags", "lang"];
+
+/// Where a repository root came from
+#[derive(Clone, Debug, PartialEq)]
+pub enum RepoSource {
+    /// Passed explicitly, typically from a command line flag
+    Flag,
+    /// The [`REPO_DIR_ENV_VAR`] environment variable
+    EnvVar,
+    /// The `repo_dir` key of a config file
+    ConfigFile(PathBuf),
+    /// The current directory or one of its ancestors
+    Ancestor,
+    /// ~/oss/fonts, where our tools have historically expected to find the repository
+    Home,
+}
+
+impl fmt::Display for RepoSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RepoSource::Flag => write!(f, "command line"),
+            RepoSource::EnvVar => write!(f, "${REPO_DIR_ENV_VAR}"),
+            RepoSource::ConfigFile(file) => write!(f, "{}", file.display()),
+            RepoSource::Ancestor => write!(f, "current directory"),
+            RepoSource::Home => write!(f, "default location"),
+        }
+    }
+}
+
+/// Whether dir looks like the root of a google/fonts checkout
+pub fn is_repo_dir(dir: &Path) -> bool {
+    REPO_MARKERS.iter().all(|m| dir.join(m).is_dir())
+}
+
+/// The config file, $XDG_CONFIG_HOME/gf-metadata/config or ~/.config/gf-metadata/config
+pub fn config_file() -> Option<PathBuf> {
+    env::var_os("XDG_CONFIG_HOME")
+        .filter(|d| !d.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| home::home_dir().map(|h| h.join(".config")))
+        .map(|d| d.join("gf-metadata/config"))
+}
+
+/// Read repo_dir from a config file of `key = value` lines, # starts a comment.
+///
+/// A relative repo_dir is relative to the config file. A missing config file is not an error.
+fn repo_dir_from_config(file: &Path) -> Result<Option<PathBuf>, GfMetadataError> {
+    let content = match fs::read_to_string(file) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(GfMetadataError::io(file, e)),
+    };
+    Ok(content
+        .lines()
+        .map(|l| l.split('#').next().unwrap_or_default())
+        .filter_map(|l| l.split_once('='))
+        .find(|(key, _)| key.trim() == "repo_dir")
+        .map(|(_, value)| {
+            let value = value.trim().trim_matches('"');
+            file.parent().unwrap_or(Path::new("")).join(value)
+        }))
+}
+
+/// Resolve the repository root, in order of precedence, from flag, env_var, config_file,
+/// by walking up from cwd or from the historical default location under home.
+///
+/// An explicitly configured directory that doesn't exist is an error, not a cue to keep
+/// looking: it's almost certainly a typo.
+pub(crate) fn resolve_repo_dir(
+    flag: Option<&Path>,
+    env_var: Option<OsString>,
+    config_file: Option<&Path>,
+    cwd: Option<&Path>,
+    home: Option<&Path>,
+) -> Result<(PathBuf, RepoSource), GfMetadataError> {
+    let configured = match (flag, env_var.filter(|v| !v.is_empty())) {
+        (Some(flag), _) => Some((flag.to_path_buf(), RepoSource::Flag)),
+        (None, Some(env_var)) => Some((PathBuf::from(env_var), RepoSource::EnvVar)),
+        (None, None) => match config_file {
+            Some(file) => repo_dir_from_config(file)?
+                .map(|dir| (dir, RepoSource::ConfigFile(file.to_path_buf()))),
+            None => None,
+        },
+    };
+    if let Some((dir, source)) = configured {
+        if !dir.is_dir() {
+            return Err(GfMetadataError::MissingDirectory(dir));
+        }
+        return Ok((dir, source));
+    }
+
+    if let Some(dir) = cwd.and_then(|cwd| cwd.ancestors().find(|d| is_repo_dir(d))) {
+        return Ok((dir.to_path_buf(), RepoSource::Ancestor));
+    }
+    if let Some(dir) = home.map(|h| h.join("oss/fonts")).filter(|d| is_repo_dir(d)) {
+        return Ok((dir, RepoSource::Home));
+    }
+    Err(GfMetadataError::RepoNotFound)
+}
+
+/// Find the google/fonts repository root, see [`crate::GoogleFonts::discover`]
+pub fn discover_repo_dir(flag: Option<&Path>) -> Result<(PathBuf, RepoSource), GfMetadataError> {
+    resolve_repo_dir(
+        flag,
+        env::var_os(REPO_DIR_ENV_VAR),
+        config_file().as_deref(),
+        env::current_dir().ok().as_deref(),
+        home::home_dir().as_deref(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{fake_repo, temp_dir};
+
+    #[test]
+    fn discover_precedence() {
+        let root = temp_dir("discover_precedence");
+        let (a, b) = (root.join("a"), root.join("b"));
+        fake_repo(&a);
+        fake_repo(&b);
+        let config = root.join("config");
+        fs::write(&config, "# where fonts live\nrepo_dir = b\n").unwrap();
+        let cwd = a.join("ofl/roboto");
+
+        let resolve = |flag: Option<&Path>, env: Option<&Path>, config: Option<&Path>| {
+            resolve_repo_dir(
+                flag,
+                env.map(|e| e.as_os_str().to_owned()),
+                config,
+                Some(&cwd),
+                None,
+            )
+            .unwrap()
+        };
+        assert_eq!(
+            (b.clone(), RepoSource::Flag),
+            resolve(Some(&b), Some(&a), None)
+        );
+        assert_eq!(
+            (b.clone(), RepoSource::EnvVar),
+            resolve(None, Some(&b), None)
+        );
+        assert_eq!(
+            (b.clone(), RepoSource::ConfigFile(config.clone())),
+            resolve(None, None, Some(&config))
+        );
+        assert_eq!((a.clone(), RepoSource::Ancestor), resolve(None, None, None));
+        assert_eq!(
+            (a.clone(), RepoSource::Ancestor),
+            resolve(None, None, Some(&root.join("no_such_config")))
+        );
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn discover_failures() {
+        let root = temp_dir("discover_failures");
+        let missing = root.join("missing");
+        assert!(matches!(
+            resolve_repo_dir(Some(&missing), None, None, Some(&root), None),
+            Err(GfMetadataError::MissingDirectory(p)) if p == missing
+        ));
+        assert!(matches!(
+            resolve_repo_dir(None, None, None, Some(&root), Some(&root)),
+            Err(GfMetadataError::RepoNotFound)
+        ));
+
+        fake_repo(&root.join("oss/fonts"));
+        assert_eq!(
+            (root.join("oss/fonts"), RepoSource::Home),
+            resolve_repo_dir(None, None, None, Some(&root), Some(&root)).unwrap()
+        );
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,212 @@
+use std::{fmt, io, path::PathBuf};
+
+use protobuf::text_format::ParseError;
+use skrifa::raw::ReadError;
+
+/// Errors produced while loading metadata from a Google Fonts checkout.
+///
+/// Each variant carries the path of the offending input so callers can report
+/// and skip bad files rather than aborting an entire run.
+#[derive(Debug)]
+pub enum GfMetadataError {
+    /// A file or directory could not be read.
+    Io { path: PathBuf, error: io::Error },
+    /// A textproto, such as a METADATA.pb or a language file, failed to parse.
+    ///
+    /// `path` is populated when the textproto was read from a file.
+    TextFormat {
+        path: Option<PathBuf>,
+        line: Option<u32>,
+        error: ParseError,
+    },
+    /// A textproto parsed but leaves a required field, such as `fonts[0].filename`, unset.
+    MissingField { path: Option<PathBuf>, field: String },
+    /// A csv record, such as a line of a tags file, failed to parse.
+    ///
+    /// `path` and `line` are populated when the record was read from a file.
+    Csv {
+        path: Option<PathBuf>,
+        line: Option<usize>,
+        message: String,
+    },
+    /// A directory we expected to find in the repository does not exist.
+    MissingDirectory(PathBuf),
+    /// No binary could be located for the named font file.
+    MissingFontBinary(String),
+    /// A font binary could not be parsed.
+    Font { path: PathBuf, error: ReadError },
+    /// No google/fonts checkout could be found.
+    RepoNotFound,
+    /// No language, not even the fallback, could be found for the named family.
+    NoLanguage(String),
+}
+
+impl GfMetadataError {
+    pub(crate) fn io(path: impl Into<PathBuf>, error: io::Error) -> Self {
+        GfMetadataError::Io {
+            path: path.into(),
+            error,
+        }
+    }
+
+    pub(crate) fn missing_field(field: impl Into<String>) -> Self {
+        GfMetadataError::MissingField {
+            path: None,
+            field: field.into(),
+        }
+    }
+
+    pub(crate) fn csv(message: impl Into<String>) -> Self {
+        GfMetadataError::Csv {
+            path: None,
+            line: None,
+            message: message.into(),
+        }
+    }
+
+    /// Attach the 1-based line a csv error came from. Has no effect on other kinds of error.
+    pub(crate) fn with_line(self, line_number: usize) -> Self {
+        match self {
+            GfMetadataError::Csv { path, message, .. } => GfMetadataError::Csv {
+                path,
+                line: Some(line_number),
+                message,
+            },
+            e => e,
+        }
+    }
+
+    /// Attach the file a csv or textproto error came from. Has no effect on other kinds of error.
+    pub(crate) fn with_path(self, file: impl Into<PathBuf>) -> Self {
+        match self {
+            GfMetadataError::Csv { line, message, .. } => GfMetadataError::Csv {
+                path: Some(file.into()),
+                line,
+                message,
+            },
+            GfMetadataError::TextFormat { line, error, .. } => GfMetadataError::TextFormat {
+                path: Some(file.into()),
+                line,
+                error,
+            },
+            GfMetadataError::MissingField { field, .. } => GfMetadataError::MissingField {
+                path: Some(file.into()),
+                field,
+            },
+            e => e,
+        }
+    }
+
+    /// The file or directory the error relates to, if known
+    pub fn path(&self) -> Option<&PathBuf> {
+        match self {
+            GfMetadataError::Io { path, .. }
+            | GfMetadataError::MissingDirectory(path)
+            | GfMetadataError::Font { path, .. } => Some(path),
+            GfMetadataError::TextFormat { path, .. }
+            | GfMetadataError::MissingField { path, .. }
+            | GfMetadataError::Csv { path, .. } => path.as_ref(),
+            GfMetadataError::MissingFontBinary(..)
+            | GfMetadataError::RepoNotFound
+            | GfMetadataError::NoLanguage(..) => None,
+        }
+    }
+}
+
+impl fmt::Display for GfMetadataError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GfMetadataError::Io { path, error } => write!(f, "Unable to read {path:?}: {error}"),
+            GfMetadataError::TextFormat { path, error, .. } => match path {
+                Some(path) => write!(f, "Unable to parse {path:?}: {error}"),
+                None => write!(f, "Unable to parse: {error}"),
+            },
+            GfMetadataError::MissingField { path, field } => match path {
+                Some(path) => write!(f, "{path:?} doesn't set required field {field}"),
+                None => write!(f, "Required field {field} is not set"),
+            },
+            GfMetadataError::Csv {
+                path,
+                line,
+                message,
+            } => {
+                match (path, line) {
+                    (Some(path), Some(line)) => write!(f, "{path:?} line {line}: ")?,
+                    (Some(path), None) => write!(f, "{path:?}: ")?,
+                    (None, Some(line)) => write!(f, "line {line}: ")?,
+                    (None, None) => (),
+                }
+                write!(f, "{message}")
+            }
+            GfMetadataError::MissingDirectory(path) => write!(f, "No such directory as {path:?}"),
+            GfMetadataError::MissingFontBinary(filename) => {
+                write!(f, "Unable to locate a binary for {filename}")
+            }
+            GfMetadataError::Font { path, error } => {
+                write!(f, "Unable to parse font {path:?}: {error}")
+            }
+            GfMetadataError::RepoNotFound => write!(
+                f,
+                "Unable to find google/fonts, pass its path, set ${} or run from inside a checkout",
+                crate::REPO_DIR_ENV_VAR
+            ),
+            GfMetadataError::NoLanguage(family) => write!(
+                f,
+                "No language for {family}, not even {}, are the gflanguages files missing?",
+                crate::FALLBACK_LANGUAGE
+            ),
+        }
+    }
+}
+
+impl std::error::Error for GfMetadataError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            GfMetadataError::Io { error, .. } => Some(error),
+            GfMetadataError::TextFormat { error, .. } => Some(error),
+            GfMetadataError::Font { error, .. } => Some(error),
+            GfMetadataError::Csv { .. }
+            | GfMetadataError::MissingField { .. }
+            | GfMetadataError::MissingDirectory(..)
+            | GfMetadataError::MissingFontBinary(..)
+            | GfMetadataError::RepoNotFound
+            | GfMetadataError::NoLanguage(..) => None,
+        }
+    }
+}
+
+impl From<ParseError> for GfMetadataError {
+    fn from(error: ParseError) -> Self {
+        // ParseError doesn't expose its location but does display it as line:col: message
+        let line = error
+            .to_string()
+            .split(':')
+            .next()
+            .and_then(|l| l.parse().ok());
+        GfMetadataError::TextFormat {
+            path: None,
+            line,
+            error,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::read_family;
+
+    #[test]
+    fn text_format_error_has_line() {
+        let err = read_family("name: \"Roboto\"\nnot_a_field: 1\n")
+            .unwrap_err()
+            .with_path("METADATA.pb");
+        let GfMetadataError::TextFormat { path, line, .. } = err else {
+            panic!("Wrong error type");
+        };
+        assert_eq!(
+            (Some(PathBuf::from("METADATA.pb")), Some(2)),
+            (path, line)
+        );
+    }
+}
@@ -0,0 +1,292 @@
+//! Choosing the font that represents a family, e.g. in test images.
+
+use std::{fmt, str::FromStr};
+
+use crate::{FamilyProto, FontProto, GfMetadataError, GoogleFonts, TagIndex, position};
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum FontStyle {
+    Normal,
+    Italic,
+}
+
+impl FontStyle {
+    fn style(&self) -> &str {
+        match self {
+            FontStyle::Normal => "normal",
+            FontStyle::Italic => "italic",
+        }
+    }
+}
+
+/// Whether font is a variable font
+///
+/// A family that declares axes is variable, except for any static instances listed alongside
+/// its variable fonts, which METADATA.pb marks by giving their position.
+pub fn is_variable(family: &FamilyProto, font: &FontProto) -> bool {
+    !family.axes.is_empty() && !position::has_positions(font)
+}
+
+/// Decides which font of a family is its exemplar
+pub trait ExemplarPolicy: Send + Sync {
+    /// How well font represents family, the highest scoring font is the exemplar.
+    ///
+    /// Ties are broken by [`DefaultPolicy`] and then by order in the family.
+    fn score(&self, family: &FamilyProto, font: &FontProto) -> f32;
+}
+
+/// The font closest to a style and weight, preferring heavier and then variable fonts
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct DefaultPolicy {
+    pub style: FontStyle,
+    pub weight: i32,
+}
+
+impl Default for DefaultPolicy {
+    /// Normal 400, the regular
+    fn default() -> Self {
+        Self {
+            style: FontStyle::Normal,
+            weight: 400,
+        }
+    }
+}
+
+impl ExemplarPolicy for DefaultPolicy {
+    fn score(&self, family: &FamilyProto, font: &FontProto) -> f32 {
+        let mut score = 0;
+        // prefer preferred_style
+        if font.style() == self.style.style() {
+            score += 16;
+        }
+
+        // prefer closer to preferred_weight
+        score -= (font.weight() - self.weight).abs() / 100;
+
+        // prefer more weight to less weight
+        if font.weight() > self.weight {
+            score += 1;
+        }
+
+        // prefer variable
+        if is_variable(family, font) {
+            score += 2;
+        }
+
+        score as f32
+    }
+}
+
+/// Prefer static fonts, e.g. to avoid rendering a variable font at its default location
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct PreferStatic;
+
+impl ExemplarPolicy for PreferStatic {
+    fn score(&self, family: &FamilyProto, font: &FontProto) -> f32 {
+        if is_variable(family, font) { 0.0 } else { 1.0 }
+    }
+}
+
+/// Prefer italic fonts
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct PreferItalic;
+
+impl ExemplarPolicy for PreferItalic {
+    fn score(&self, _: &FamilyProto, font: &FontProto) -> f32 {
+        if font.style() == FontStyle::Italic.style() {
+            1.0
+        } else {
+            0.0
+        }
+    }
+}
+
+/// The font whose weight is closest to a target weight
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ClosestWeight(pub i32);
+
+impl ExemplarPolicy for ClosestWeight {
+    fn score(&self, _: &FamilyProto, font: &FontProto) -> f32 {
+        -((font.weight() - self.0).abs() as f32)
+    }
+}
+
+/// The font with the highest value for a tag, e.g. /Expressive/Loud
+///
+/// Fonts get the value tagged at their location or failing that the value for the family.
+/// Fonts without a value for the tag score lowest.
+pub struct TagValue<'a> {
+    pub tags: &'a TagIndex,
+    pub tag: String,
+}
+
+impl ExemplarPolicy for TagValue<'_> {
+    fn score(&self, family: &FamilyProto, font: &FontProto) -> f32 {
+        self.tags
+            .tags_for_font(family, font)
+            .into_iter()
+            .find(|t| t.tag == self.tag)
+            .map(|t| t.value)
+            .unwrap_or(f32::NEG_INFINITY)
+    }
+}
+
+/// The exemplar for family per policy, None if family has no fonts
+pub fn select_exemplar<'a>(
+    family: &'a FamilyProto,
+    policy: &dyn ExemplarPolicy,
+) -> Option<&'a FontProto> {
+    let default = DefaultPolicy::default();
+    let score = |font| (policy.score(family, font), default.score(family, font));
+    family
+        .fonts
+        .iter()
+        .reduce(|acc, e| if score(acc) >= score(e) { acc } else { e })
+}
+
+/// A policy by name, e.g. from a command line flag
+///
+/// Parses from, and displays as, one of default, static, italic, weight=N or tag=TAG.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum ExemplarPreference {
+    #[default]
+    Default,
+    Static,
+    Italic,
+    Weight(i32),
+    Tag(String),
+}
+
+impl ExemplarPreference {
+    /// The policy, tag based policies use the tags of gf
+    pub fn policy<'a>(
+        &self,
+        gf: &'a GoogleFonts,
+    ) -> Result<Box<dyn ExemplarPolicy + 'a>, &'a GfMetadataError> {
+        Ok(match self {
+            ExemplarPreference::Default => Box::new(DefaultPolicy::default()),
+            ExemplarPreference::Static => Box::new(PreferStatic),
+            ExemplarPreference::Italic => Box::new(PreferItalic),
+            ExemplarPreference::Weight(weight) => Box::new(ClosestWeight(*weight)),
+            ExemplarPreference::Tag(tag) => Box::new(TagValue {
+                tags: gf.tag_index()?,
+                tag: tag.clone(),
+            }),
+        })
+    }
+}
+
+impl FromStr for ExemplarPreference {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once('=') {
+            None if s == "default" => Ok(ExemplarPreference::Default),
+            None if s == "static" => Ok(ExemplarPreference::Static),
+            None if s == "italic" => Ok(ExemplarPreference::Italic),
+            Some(("weight", weight)) => weight
+                .trim()
+                .parse()
+                .map(ExemplarPreference::Weight)
+                .map_err(|_| format!("Invalid weight {weight:?}")),
+            Some(("tag", tag)) if !tag.trim().is_empty() => {
+                Ok(ExemplarPreference::Tag(tag.trim().to_string()))
+            }
+            _ => Err(format!(
+                "Unknown exemplar preference {s:?}, expected one of default, static, italic, \
+                 weight=N or tag=TAG"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for ExemplarPreference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExemplarPreference::Default => write!(f, "default"),
+            ExemplarPreference::Static => write!(f, "static"),
+            ExemplarPreference::Italic => write!(f, "italic"),
+            ExemplarPreference::Weight(weight) => write!(f, "weight={weight}"),
+            ExemplarPreference::Tag(tag) => write!(f, "tag={tag}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Tag, read_family, test_util::testdata_file_content};
+
+    #[test]
+    fn variable_fonts_from_axes() {
+        let wix = read_family(&testdata_file_content("wixmadefortext-metadata.pb")).unwrap();
+        let kosugi = read_family(&testdata_file_content("kosugimaru-metadata.pb")).unwrap();
+        assert_eq!(
+            vec![true, true, false, false],
+            wix.fonts[..4]
+                .iter()
+                .map(|f| is_variable(&wix, f))
+                .collect::<Vec<_>>()
+        );
+        assert!(!is_variable(&kosugi, &kosugi.fonts[0]));
+    }
+
+    #[test]
+    fn exemplar_policies() {
+        let wix = read_family(&testdata_file_content("wixmadefortext-metadata.pb")).unwrap();
+        let tags = TagIndex::new(
+            &[
+                Tag::from_str("Wix Madefor Text,/Expressive/Loud,10").unwrap(),
+                Tag::from_str("Wix Madefor Text,wght@700,/Expressive/Loud,90").unwrap(),
+            ],
+            &[],
+        );
+        let select = |policy: &dyn ExemplarPolicy| {
+            select_exemplar(&wix, policy)
+                .unwrap()
+                .filename()
+                .to_string()
+        };
+        assert_eq!(
+            "WixMadeforText[wght].ttf",
+            select(&DefaultPolicy::default())
+        );
+        assert_eq!("WixMadeforText-Regular.ttf", select(&PreferStatic));
+        assert_eq!("WixMadeforText-Italic[wght].ttf", select(&PreferItalic));
+        assert_eq!("WixMadeforText-Bold.ttf", select(&ClosestWeight(680)));
+        assert_eq!(
+            "WixMadeforText-Bold.ttf",
+            select(&TagValue {
+                tags: &tags,
+                tag: "/Expressive/Loud".to_string()
+            })
+        );
+        assert_eq!(
+            "WixMadeforText[wght].ttf",
+            select(&TagValue {
+                tags: &tags,
+                tag: "/Quality/Drawing".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn parse_exemplar_preference() {
+        for s in [
+            "default",
+            "static",
+            "italic",
+            "weight=700",
+            "tag=/Expressive/Loud",
+        ] {
+            assert_eq!(s, ExemplarPreference::from_str(s).unwrap().to_string());
+        }
+        assert_eq!(
+            ExemplarPreference::Weight(300),
+            ExemplarPreference::from_str("weight=300").unwrap()
+        );
+        for s in ["", "bold", "weight=heavy", "tag="] {
+            assert!(ExemplarPreference::from_str(s).is_err(), "{s}");
+        }
+    }
+}
@@ -1,30 +1,92 @@
+mod axes;
+mod axis_registry;
+mod cache;
+mod coverage;
+mod css;
+mod csv;
+mod diff;
+mod discover;
+mod error;
+mod exemplar;
 mod fonts_public;
 mod languages_public;
+mod names;
+mod position;
+mod primary_language;
+mod query;
+mod sample_text;
+mod tags;
+#[cfg(test)]
+mod test_util;
+mod text_format;
+mod validate;
 
+use cache::{Cacheable, MetadataCache, Section};
 use std::{
-    cell::OnceCell,
     collections::HashMap,
-    fs::{self, File},
-    io::{BufRead, BufReader, Error, ErrorKind},
+    fs,
     path::{Path, PathBuf},
-    str::FromStr,
+    sync::OnceLock,
 };
 
+pub use axes::{AxisProto, FallbackProto};
+pub use axis_registry::{AxisGrid, FamilyAxis, GridAxis, GridValue, named_position};
+pub use cache::default_cache_file;
+pub use coverage::{
+    LanguageCoverage, LanguageSupport, MissingChars, exemplar_chars, language_coverage,
+};
+pub use css::{FontFaceCss, fallback_family_name, font_stack};
+pub use diff::{Change, FamilyDiff, SnapshotDiff};
+pub use discover::{REPO_DIR_ENV_VAR, RepoSource, config_file, discover_repo_dir, is_repo_dir};
+pub use error::GfMetadataError;
+pub use exemplar::{
+    ClosestWeight, DefaultPolicy, ExemplarPolicy, ExemplarPreference, FontStyle, PreferItalic,
+    PreferStatic, TagValue, is_variable, select_exemplar,
+};
 pub use fonts_public::*;
 pub use languages_public::{
     ExemplarCharsProto, LanguageProto, RegionProto, SampleTextProto, ScriptProto,
 };
+use names::NameIndex;
+pub use position::{FontPosition, font_positions, set_font_positions};
+pub use primary_language::{FALLBACK_LANGUAGE, LanguageSource, PrimaryLanguage};
 use protobuf::text_format::ParseError;
+pub use query::FamilyQuery;
+use rayon::prelude::*;
 use regex::Regex;
+pub use sample_text::{
+    SampleText, SampleTextField, SampleTextPurpose, SampleTextSelector, SampleTextSource,
+};
+use skrifa::{FontRef, MetadataProvider};
+pub use tags::{
+    AxisLocation, Tag, TagIndex, TagMetadata, merge_tags, merge_tags_file, read_tag_metadata,
+    read_tags, write_tag_metadata, write_tags,
+};
+pub use validate::{FamilyReport, Finding, RULES, Rule, Severity, ValidationReport};
 use walkdir::WalkDir;
 
-pub fn read_family(s: &str) -> Result<FamilyProto, ParseError> {
+/// Parse a METADATA.pb, see [`font_positions`] for the undocumented position field.
+///
+/// Unlike [`protobuf::text_format::parse_from_str`] a missing required field is reported by name.
+pub fn read_family(s: &str) -> Result<FamilyProto, GfMetadataError> {
     if s.contains("position") {
-        let re = Regex::new(r"(?m)position\s+\{[^}]*\}").expect("Valid re");
-        let s = re.replace_all(s, "");
-        protobuf::text_format::parse_from_str(&s)
-    } else {
-        protobuf::text_format::parse_from_str(s)
+        return position::parse_family(s);
+    }
+    let mut family = FamilyProto::new();
+    protobuf::text_format::merge_from_str(&mut family, s)?;
+    match text_format::missing_required_field(&family) {
+        Some(field) => Err(GfMetadataError::missing_field(field)),
+        None => Ok(family),
+    }
+}
+
+/// Write family as text format in the canonical form google/fonts uses for METADATA.pb
+///
+/// An unmodified METADATA.pb written by the google/fonts tooling round trips byte for byte.
+pub fn write_family(family: &FamilyProto) -> String {
+    match position::extended_family(family) {
+        Some(extended) => text_format::write_message(&*extended),
+        None => text_format::write_message(family),
     }
 }
 
@@ -32,52 +94,21 @@ pub fn read_language(s: &str) -> Result<LanguageProto, ParseError> {
     protobuf::text_format::parse_from_str(s)
 }
 
-fn exemplar_score(font: &FontProto, preferred_style: FontStyle, preferred_weight: i32) -> i32 {
-    let mut score = 0;
-    // prefer preferred_style
-    if font.style() == preferred_style.style() {
-        score += 16;
-    }
-
-    // prefer closer to preferred_weight
-    score -= (font.weight() - preferred_weight).abs() / 100;
-
-    // prefer more weight to less weight
-    if font.weight() > preferred_weight {
-        score += 1;
-    }
-
-    // prefer variable
-    if font.filename().contains("].") {
-        score += 2;
-    }
-
-    score
+pub fn read_region(s: &str) -> Result<RegionProto, ParseError> {
+    protobuf::text_format::parse_from_str(s)
 }
 
-pub fn exemplar(family: &FamilyProto) -> Option<&FontProto> {
-    fn score(font: &FontProto) -> i32 {
-        exemplar_score(font, FontStyle::Normal, 400)
-    }
-    family
-        .fonts
-        .iter()
-        .reduce(|acc, e| if score(acc) >= score(e) { acc } else { e })
+pub fn read_script(s: &str) -> Result<ScriptProto, ParseError> {
+    protobuf::text_format::parse_from_str(s)
 }
 
-#[derive(Copy, Clone, Debug, PartialEq)]
-pub enum FontStyle {
-    Normal,
-    Italic,
+pub fn read_axis(s: &str) -> Result<AxisProto, ParseError> {
+    protobuf::text_format::parse_from_str(s)
 }
 
-impl FontStyle {
-    fn style(&self) -> &str {
-        match self {
-            FontStyle::Normal => "normal",
-            FontStyle::Italic => "italic",
-        }
-    }
+/// The font that best represents family, see [`DefaultPolicy`] and [`select_exemplar`]
+pub fn exemplar(family: &FamilyProto) -> Option<&FontProto> {
+    select_exemplar(family, &DefaultPolicy::default())
 }
 
 pub fn select_font(
@@ -85,184 +116,186 @@ pub fn select_font(
     preferred_style: FontStyle,
     preferred_weight: i32,
 ) -> Option<&FontProto> {
-    let score =
-        |font: &FontProto| -> i32 { exemplar_score(font, preferred_style, preferred_weight) };
-    family
-        .fonts
-        .iter()
-        .reduce(|acc, e| if score(acc) >= score(e) { acc } else { e })
+    select_exemplar(
+        family,
+        &DefaultPolicy {
+            style: preferred_style,
+            weight: preferred_weight,
+        },
+    )
 }
 
-fn iter_families(
+fn walk_error(root: &Path, e: walkdir::Error) -> GfMetadataError {
+    let path = e.path().unwrap_or(root).to_path_buf();
+    GfMetadataError::io(
+        path,
+        e.into_io_error()
+            .unwrap_or_else(|| std::io::Error::other("Filesystem loop")),
+    )
+}
+
+pub(crate) fn missing_dir(root: &Path) -> Option<GfMetadataError> {
+    (!root.is_dir()).then(|| GfMetadataError::MissingDirectory(root.to_path_buf()))
+}
+
+/// Walk root, collecting the files that match predicate and any errors encountered along the way
+fn find_files(
     root: &Path,
-    filter: Option<&Regex>,
-) -> impl Iterator<Item = (PathBuf, Result<FamilyProto, ParseError>)> {
+    predicate: impl Fn(&Path) -> bool,
+) -> Vec<Result<PathBuf, GfMetadataError>> {
     WalkDir::new(root)
         .into_iter()
-        .filter_map(|d| d.ok())
-        .filter(|d| d.file_name() == "METADATA.pb")
-        .filter(move |d| {
-            filter
-                .map(|r| r.find(&d.path().to_string_lossy()).is_some())
-                .unwrap_or(true)
-        })
-        .map(|d| {
-            (
-                d.path().to_path_buf(),
-                read_family(&fs::read_to_string(d.path()).expect("To read files!")),
-            )
+        .filter_map(|d| match d {
+            Ok(d) => predicate(d.path()).then(|| Ok(d.into_path())),
+            Err(e) => Some(Err(walk_error(root, e))),
         })
+        .collect()
 }
 
-pub fn iter_languages(root: &Path) -> impl Iterator<Item = Result<LanguageProto, ParseError>> {
-    WalkDir::new(root)
-        .into_iter()
-        .filter_map(|d| d.ok())
-        .filter(|d| {
-            d.path()
-                .canonicalize()
-                .unwrap()
-                .to_str()
-                .unwrap()
-                .contains("gflanguages/data/languages")
-                && d.file_name().to_string_lossy().ends_with(".textproto")
-        })
-        .map(|d| read_language(&fs::read_to_string(d.path()).expect("To read files!")))
+fn parse_file<T, E: Into<GfMetadataError>>(
+    path: &Path,
+    parse: impl Fn(&str) -> Result<T, E>,
+) -> Result<T, GfMetadataError> {
+    let s = fs::read_to_string(path).map_err(|e| GfMetadataError::io(path, e))?;
+    parse(&s).map_err(|e| e.into().with_path(path))
 }
 
-pub fn read_tags(root: &Path) -> Result<Vec<Tag>, Error> {
-    let mut tag_dir = root.to_path_buf();
-    tag_dir.push("tags/all");
-    let mut tags = Vec::new();
-    for entry in fs::read_dir(&tag_dir).expect("To read tag dir") {
-        let entry = entry.expect("To access tag dir entries");
-        if entry
-            .path()
-            .extension()
-            .expect("To have extensions")
-            .to_str()
-            .expect("utf-8")
-            != "csv"
-        {
-            continue;
-        }
-        let fd = File::open(&entry.path())?;
-        let rdr = BufReader::new(fd);
-        tags.extend(
-            rdr.lines()
-                .map(|s| s.expect("Valid tag lines"))
-                .map(|s| Tag::from_str(&s).expect("Valid tag lines")),
-        );
-    }
-    Ok(tags)
+/// Loads every METADATA.pb under root using load.
+///
+/// The walk is serial but files are loaded in parallel; results are in walk order.
+fn iter_families(
+    root: &Path,
+    filter: Option<&Regex>,
+    load: impl Fn(&Path) -> Result<FamilyProto, GfMetadataError> + Sync,
+) -> impl Iterator<Item = (PathBuf, Result<FamilyProto, GfMetadataError>)> {
+    let missing = missing_dir(root).map(|e| (root.to_path_buf(), Err(e)));
+    let files = if missing.is_none() {
+        find_files(root, |p| {
+            p.file_name().is_some_and(|n| n == "METADATA.pb")
+                && filter
+                    .map(|r| r.find(&p.to_string_lossy()).is_some())
+                    .unwrap_or(true)
+        })
+    } else {
+        Vec::new()
+    };
+    let families: Vec<_> = files
+        .into_par_iter()
+        .map(|f| match f {
+            Ok(p) => {
+                let family = load(&p);
+                (p, family)
+            }
+            Err(e) => (
+                e.path().cloned().unwrap_or_else(|| root.to_path_buf()),
+                Err(e),
+            ),
+        })
+        .collect();
+    missing.into_iter().chain(families)
 }
 
-pub fn read_tag_metadata(root: &Path) -> Result<Vec<TagMetadata>, Error> {
-    let mut tag_metadata_file = root.to_path_buf();
-    tag_metadata_file.push("tags/tags_metadata.csv");
-    let mut metadata = Vec::new();
+/// Where gflanguages keeps its data in a google/fonts checkout
+const GFLANGUAGES_DATA_DIR: &str = "lang/Lib/gflanguages/data";
 
-    let fd = File::open(&tag_metadata_file)?;
-    let rdr = BufReader::new(fd);
-    metadata.extend(
-        rdr.lines()
-            .map(|s| s.expect("Valid tag lines"))
-            .map(|s| TagMetadata::from_str(&s).expect("Valid tag metadata lines")),
-    );
+/// Where the axis registry keeps its textprotos in a google/fonts checkout
+const AXIS_REGISTRY_DATA_DIR: &str = "axisregistry/Lib/axisregistry/data";
 
-    Ok(metadata)
+/// Loads every textproto in a gflanguages data directory, e.g. languages, under root.
+fn iter_gflanguages_data<T: Send>(
+    root: &Path,
+    data_dir: &str,
+    load: impl Fn(&Path) -> Result<T, GfMetadataError> + Sync,
+) -> impl Iterator<Item = Result<T, GfMetadataError>> {
+    iter_textprotos(
+        root,
+        &format!("{GFLANGUAGES_DATA_DIR}/{data_dir}"),
+        &format!("gflanguages/data/{data_dir}/"),
+        load,
+    )
+    .into_iter()
 }
 
-fn csv_values(s: &str) -> Vec<&str> {
-    let mut s = s;
-    let mut values = Vec::new();
-    while !s.is_empty() {
-        s = s.trim();
-        let mut end_idx = None;
-        if s.starts_with('"') {
-            end_idx = Some(*(&s[1..].find('"').expect("Close quote")));
-        }
-        end_idx = s[end_idx.unwrap_or_default()..]
-            .find(',')
-            .map(|v| v + end_idx.unwrap_or_default());
-        if let Some(end_idx) = end_idx {
-            let (value, rest) = s.split_at(end_idx);
-            values.push(value.trim());
-            s = &rest[1..];
-        } else {
-            values.push(s);
-            s = "";
+/// Loads every textproto in data_dir, a directory relative to the root of google/fonts.
+///
+/// Only data_dir is walked, not the whole checkout. If root is instead inside a data
+/// directory, e.g. that of a gflanguages checkout, root is walked and files whose path
+/// contains marker are kept. Neither means there's no data.
+///
+/// The walk is serial but files are loaded in parallel; results are in walk order.
+fn iter_textprotos<T: Send>(
+    root: &Path,
+    data_dir: &str,
+    marker: &str,
+    load: impl Fn(&Path) -> Result<T, GfMetadataError> + Sync,
+) -> Vec<Result<T, GfMetadataError>> {
+    let is_textproto = |p: &Path| p.extension().is_some_and(|e| e == "textproto");
+    // Canonicalize so we can spot the data dir even if root is inside it
+    let files = match root.canonicalize() {
+        Ok(root) if root.join(data_dir).is_dir() => find_files(&root.join(data_dir), is_textproto),
+        Ok(root) if root.is_dir() && format!("{}/", root.display()).contains(marker) => {
+            find_files(&root, |p| {
+                p.to_string_lossy().contains(marker) && is_textproto(p)
+            })
         }
-    }
-    values
+        Ok(root) if root.is_dir() => Vec::new(),
+        _ => vec![Err(GfMetadataError::MissingDirectory(root.to_path_buf()))],
+    };
+    files
+        .into_par_iter()
+        .map(|f| f.and_then(|p| load(&p)))
+        .collect()
 }
 
-#[derive(Clone, Debug)]
-pub struct Tag {
-    pub family: String,
-    pub loc: String,
-    pub tag: String,
-    pub value: f32,
+/// Parses every language textproto under root.
+pub fn iter_languages(root: &Path) -> impl Iterator<Item = Result<LanguageProto, GfMetadataError>> {
+    iter_gflanguages_data(root, "languages", |p| parse_file(p, read_language))
 }
 
-impl FromStr for Tag {
-    type Err = Error;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let values = csv_values(s);
-        let (family, loc, tag, value) = match values[..] {
-            [family, tag, value] => (family, "", tag, value),
-            [family, loc, tag, value] => (family, loc, tag, value),
-            _ => return Err(Error::new(ErrorKind::InvalidData, "Unparseable tag")),
-        };
-        Ok(Tag {
-            family: family.to_string(),
-            loc: loc.to_string(),
-            tag: tag.to_string(),
-            value: f32::from_str(value)
-                .map_err(|_| Error::new(ErrorKind::InvalidData, "Invalid tag value"))?,
-        })
-    }
+/// Parses every region textproto under root.
+pub fn iter_regions(root: &Path) -> impl Iterator<Item = Result<RegionProto, GfMetadataError>> {
+    iter_gflanguages_data(root, "regions", |p| parse_file(p, read_region))
 }
 
-#[derive(Clone, Debug)]
-pub struct TagMetadata {
-    pub tag: String,
-    pub min_value: f32,
-    pub max_value: f32,
-    pub prompt_name: String,
+/// Parses every script textproto under root.
+pub fn iter_scripts(root: &Path) -> impl Iterator<Item = Result<ScriptProto, GfMetadataError>> {
+    iter_gflanguages_data(root, "scripts", |p| parse_file(p, read_script))
 }
 
-impl FromStr for TagMetadata {
-    type Err = Error;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let values = csv_values(s);
-        let [tag, min, max, prompt_name] = values[..] else {
-            return Err(Error::new(
-                ErrorKind::InvalidData,
-                "Unparseable tag metadata, wrong number of values",
-            ));
-        };
-        Ok(TagMetadata {
-            tag: tag.into(),
-            min_value: f32::from_str(min)
-                .map_err(|_| Error::new(ErrorKind::InvalidData, "Invalid min value"))?,
-            max_value: f32::from_str(max)
-                .map_err(|_| Error::new(ErrorKind::InvalidData, "Invalid min value"))?,
-            prompt_name: prompt_name.into(),
-        })
-    }
+/// Parses every axis registry textproto under root.
+///
+/// That's axisregistry/Lib/axisregistry/data in google/fonts, or root can be the
+/// Lib/axisregistry/data directory of a checkout of the axisregistry package.
+pub fn iter_axes(root: &Path) -> impl Iterator<Item = Result<AxisProto, GfMetadataError>> {
+    iter_registry_axes(root, |p| parse_file(p, read_axis)).into_iter()
 }
 
+fn iter_registry_axes(
+    root: &Path,
+    load: impl Fn(&Path) -> Result<AxisProto, GfMetadataError> + Sync,
+) -> Vec<Result<AxisProto, GfMetadataError>> {
+    iter_textprotos(root, AXIS_REGISTRY_DATA_DIR, "axisregistry/data/", load)
+}
+
+/// A lazily loaded view of a local copy of the Google Fonts repository.
+///
+/// Everything is loaded on first access and cached thereafter. Safe to share across threads.
+///
+/// Parsed metadata can also be persisted between runs, see [`Self::with_cache`].
 pub struct GoogleFonts {
     repo_dir: PathBuf,
     family_filter: Option<Regex>,
-    families: OnceCell<Vec<(PathBuf, Result<FamilyProto, ParseError>)>>,
-    languages: OnceCell<Vec<Result<LanguageProto, ParseError>>>,
-    family_by_font_file: OnceCell<HashMap<String, usize>>,
-    tags: OnceCell<Result<Vec<Tag>, Error>>,
-    tag_metadata: OnceCell<Result<Vec<TagMetadata>, Error>>,
+    cache: Option<MetadataCache>,
+    families: OnceLock<Vec<(PathBuf, Result<FamilyProto, GfMetadataError>)>>,
+    languages: OnceLock<Vec<Result<LanguageProto, GfMetadataError>>>,
+    regions: OnceLock<Vec<Result<RegionProto, GfMetadataError>>>,
+    scripts: OnceLock<Vec<Result<ScriptProto, GfMetadataError>>>,
+    axes: OnceLock<Vec<Result<AxisProto, GfMetadataError>>>,
+    family_by_font_file: OnceLock<HashMap<String, usize>>,
+    name_index: OnceLock<NameIndex>,
+    tags: OnceLock<Result<Vec<Tag>, GfMetadataError>>,
+    tag_metadata: OnceLock<Result<Vec<TagMetadata>, GfMetadataError>>,
+    tag_index: OnceLock<TagIndex>,
 }
 
 impl GoogleFonts {
@@ -270,37 +303,138 @@ impl GoogleFonts {
         Self {
             repo_dir: p,
             family_filter,
-            families: OnceCell::new(),
-            languages: OnceCell::new(),
-            family_by_font_file: OnceCell::new(),
-            tags: OnceCell::new(),
-            tag_metadata: OnceCell::new(),
+            cache: None,
+            families: OnceLock::new(),
+            languages: OnceLock::new(),
+            regions: OnceLock::new(),
+            scripts: OnceLock::new(),
+            axes: OnceLock::new(),
+            family_by_font_file: OnceLock::new(),
+            name_index: OnceLock::new(),
+            tags: OnceLock::new(),
+            tag_metadata: OnceLock::new(),
+            tag_index: OnceLock::new(),
+        }
+    }
+
+    /// Find the repository, see [`discover_repo_dir`], and load it lazily as [`Self::new`] does
+    ///
+    /// In order of precedence the repository is repo_dir, typically from a command line flag,
+    /// [`REPO_DIR_ENV_VAR`], `repo_dir` in [`config_file`], the first of the current directory
+    /// and its ancestors that [`is_repo_dir`] or ~/oss/fonts.
+    pub fn discover(
+        repo_dir: Option<&Path>,
+        family_filter: Option<Regex>,
+    ) -> Result<(Self, RepoSource), GfMetadataError> {
+        let (repo_dir, source) = discover_repo_dir(repo_dir)?;
+        Ok((Self::new(repo_dir, family_filter), source))
+    }
+
+    pub fn repo_dir(&self) -> &Path {
+        &self.repo_dir
+    }
+
+    /// Reuse metadata parsed by previous runs, persisted in cache_file by [`Self::save_cache`]
+    ///
+    /// Families, languages, regions, scripts, axes and tags are cached per source file and only
+    /// reused if that file's modification time and size are unchanged. The font file index
+    /// is derived from the families so it too comes from the cache. A missing, stale or
+    /// corrupt cache file is ignored. The repository is still walked to find files so new
+    /// and removed families are always noticed.
+    pub fn with_cache(mut self, cache_file: PathBuf) -> Self {
+        self.cache = Some(MetadataCache::load(cache_file, &self.repo_dir));
+        self
+    }
+
+    /// Write everything loaded so far to the cache file given to [`Self::with_cache`]
+    ///
+    /// Entries from previous runs for files that still exist are kept so a run that only
+    /// loads, say, families doesn't discard cached languages. Does nothing if there is no cache.
+    pub fn save_cache(&self) -> Result<(), GfMetadataError> {
+        match &self.cache {
+            Some(cache) => cache.save(),
+            None => Ok(()),
         }
     }
 
-    pub fn tags(&self) -> Result<&[Tag], &Error> {
+    /// load(path), via the cache if we have one
+    fn load_file<T: Cacheable>(
+        &self,
+        section: Section,
+        path: &Path,
+        load: impl FnOnce(&Path) -> Result<T, GfMetadataError>,
+    ) -> Result<T, GfMetadataError> {
+        match &self.cache {
+            Some(cache) => cache.get_or_load(section, path, load),
+            None => load(path),
+        }
+    }
+
+    pub fn tags(&self) -> Result<&[Tag], &GfMetadataError> {
         self.tags
-            .get_or_init(|| read_tags(&self.repo_dir))
+            .get_or_init(|| {
+                tags::read_tags_with(&self.repo_dir, |p| {
+                    self.load_file(Section::Tags, p, tags::read_csv_file)
+                })
+            })
             .as_ref()
             .map(|tags| tags.as_slice())
     }
 
-    pub fn tag_metadata(&self) -> Result<&[TagMetadata], &Error> {
+    pub fn tag_metadata(&self) -> Result<&[TagMetadata], &GfMetadataError> {
         self.tag_metadata
-            .get_or_init(|| read_tag_metadata(&self.repo_dir))
+            .get_or_init(|| {
+                tags::read_tag_metadata_with(&self.repo_dir, |p| {
+                    self.load_file(Section::TagMetadata, p, tags::read_csv_file)
+                })
+            })
             .as_ref()
             .map(|metadata| metadata.as_slice())
     }
 
-    pub fn families(&self) -> &[(PathBuf, Result<FamilyProto, ParseError>)] {
+    /// An index over [`Self::tags`], normalizing values using [`Self::tag_metadata`]
+    ///
+    /// Fails if the tags can't be loaded. If the tag metadata can't be loaded values
+    /// can't be normalized but are otherwise available.
+    pub fn tag_index(&self) -> Result<&TagIndex, &GfMetadataError> {
+        let tags = self.tags()?;
+        Ok(self
+            .tag_index
+            .get_or_init(|| TagIndex::new(tags, self.tag_metadata().unwrap_or_default())))
+    }
+
+    pub fn families(&self) -> &[(PathBuf, Result<FamilyProto, GfMetadataError>)] {
         self.families
-            .get_or_init(|| iter_families(&self.repo_dir, self.family_filter.as_ref()).collect())
+            .get_or_init(|| {
+                iter_families(&self.repo_dir, self.family_filter.as_ref(), |p| {
+                    self.load_file(Section::Families, p, |p| parse_file(p, read_family))
+                })
+                .collect()
+            })
             .as_slice()
     }
 
-    pub fn languages(&self) -> &[Result<LanguageProto, ParseError>] {
+    /// The families that match query, along with the path to their METADATA.pb
+    ///
+    /// Families that failed to load are skipped.
+    pub fn query<'a>(
+        &'a self,
+        query: &'a FamilyQuery,
+    ) -> impl Iterator<Item = (&'a Path, &'a FamilyProto)> + 'a {
+        self.families()
+            .iter()
+            .filter_map(|(p, f)| f.as_ref().ok().map(|f| (p.as_path(), f)))
+            .filter(|(_, f)| query.matches(f))
+    }
+
+    pub fn languages(&self) -> &[Result<LanguageProto, GfMetadataError>] {
         self.languages
-            .get_or_init(|| iter_languages(&self.repo_dir).collect())
+            .get_or_init(|| {
+                iter_gflanguages_data(&self.repo_dir, "languages", |p| {
+                    self.load_file(Section::Languages, p, |p| parse_file(p, read_language))
+                })
+                .collect()
+            })
             .as_slice()
     }
 
@@ -311,6 +445,97 @@ impl GoogleFonts {
             .find(|l| l.id() == lang_id)
     }
 
+    pub fn regions(&self) -> &[Result<RegionProto, GfMetadataError>] {
+        self.regions
+            .get_or_init(|| {
+                iter_gflanguages_data(&self.repo_dir, "regions", |p| {
+                    self.load_file(Section::Regions, p, |p| parse_file(p, read_region))
+                })
+                .collect()
+            })
+            .as_slice()
+    }
+
+    pub fn region(&self, region_id: &str) -> Option<&RegionProto> {
+        self.regions()
+            .iter()
+            .filter_map(|r| r.as_ref().ok())
+            .find(|r| r.id() == region_id)
+    }
+
+    pub fn scripts(&self) -> &[Result<ScriptProto, GfMetadataError>] {
+        self.scripts
+            .get_or_init(|| {
+                iter_gflanguages_data(&self.repo_dir, "scripts", |p| {
+                    self.load_file(Section::Scripts, p, |p| parse_file(p, read_script))
+                })
+                .collect()
+            })
+            .as_slice()
+    }
+
+    pub fn script(&self, script_id: &str) -> Option<&ScriptProto> {
+        self.scripts()
+            .iter()
+            .filter_map(|s| s.as_ref().ok())
+            .find(|s| s.id() == script_id)
+    }
+
+    /// The axis registry, what each axis tag means and its named positions
+    pub fn axes(&self) -> &[Result<AxisProto, GfMetadataError>] {
+        self.axes
+            .get_or_init(|| {
+                iter_registry_axes(&self.repo_dir, |p| {
+                    self.load_file(Section::Axes, p, |p| parse_file(p, read_axis))
+                })
+            })
+            .as_slice()
+    }
+
+    /// The registry definition of an axis, e.g. wght
+    pub fn axis(&self, tag: &str) -> Option<&AxisProto> {
+        self.axes()
+            .iter()
+            .filter_map(|a| a.as_ref().ok())
+            .find(|a| a.tag() == tag)
+    }
+
+    /// The axes of family, in the family's order, with their registry definitions
+    pub fn family_axes<'a>(&'a self, family: &'a FamilyProto) -> Vec<FamilyAxis<'a>> {
+        axis_registry::family_axes(self, family)
+    }
+
+    /// The languages written in a script, e.g. Latn
+    pub fn languages_for_script<'a>(
+        &'a self,
+        script_id: &str,
+    ) -> impl Iterator<Item = &'a LanguageProto> + use<'a> {
+        let script_id = script_id.to_string();
+        self.languages()
+            .iter()
+            .filter_map(|l| l.as_ref().ok())
+            .filter(move |l| l.has_script() && l.script() == script_id)
+    }
+
+    /// The languages spoken in a region, e.g. JP
+    pub fn languages_in_region<'a>(
+        &'a self,
+        region_id: &str,
+    ) -> impl Iterator<Item = &'a LanguageProto> + use<'a> {
+        let region_id = region_id.to_string();
+        self.languages()
+            .iter()
+            .filter_map(|l| l.as_ref().ok())
+            .filter(move |l| l.region.contains(&region_id))
+    }
+
+    /// The sum of the populations of the languages written in a script
+    pub fn script_population(&self, script_id: &str) -> u64 {
+        self.languages_for_script(script_id)
+            .map(|l| l.population().max(0) as u64)
+            .sum()
+    }
+
     fn family_by_font_file(&self) -> &HashMap<String, usize> {
         self.family_by_font_file.get_or_init(|| {
             self.families()
@@ -328,74 +553,141 @@ impl GoogleFonts {
         })
     }
 
+    /// The successfully loaded family at index i of [`Self::families`]
+    fn family_at(&self, i: usize) -> (&Path, &FamilyProto) {
+        let (p, f) = &self.families()[i];
+        (p.as_path(), f.as_ref().unwrap())
+    }
+
     pub fn family(&self, font: &FontProto) -> Option<(&Path, &FamilyProto)> {
         self.family_by_font_file()
             .get(font.filename())
-            .copied()
-            .map(|i| {
-                let (p, f) = &self.families()[i];
-                (p.as_path(), f.as_ref().unwrap())
-            })
+            .map(|i| self.family_at(*i))
     }
 
-    pub fn find_font_binary(&self, font: &FontProto) -> Option<PathBuf> {
-        let Some((family_path, _)) = self.family(font) else {
-            return None;
-        };
-        let mut font_file = family_path.parent().unwrap().to_path_buf();
-        font_file.push(font.filename());
-        if !font_file.exists() {
-            eprintln!("No such file as {font_file:?}");
-        }
-        font_file.exists().then_some(font_file)
+    fn name_index(&self) -> &NameIndex {
+        self.name_index
+            .get_or_init(|| NameIndex::new(&self.repo_dir, self.families()))
     }
 
-    /// Our best guess at the primary language for this family
+    /// Find a family by name, display name, directory slug such as ofl/notosanstc, or alias
     ///
-    /// Meant to be a good choice for things like rendering a sample string
-    pub fn primary_language(&self, family: &FamilyProto) -> &LanguageProto {
-        // Probe primary lang, primary script, then default baselessly to latin
-        let mut primary_language: Option<&LanguageProto> = None;
-        if primary_language.is_none() && family.has_primary_language() {
-            if let Some(lang) = self.language(family.primary_language()) {
-                primary_language = Some(lang);
-            } else {
-                eprintln!(
-                    "{} specifies invalid primary_language {}",
-                    family.name(),
-                    family.primary_language()
-                );
-            }
-        }
-        if primary_language.is_none() && family.has_primary_script() {
-            // If our script matches many languages pick the one with the highest population
-            let lang = self
-                .languages()
-                .iter()
-                .filter_map(|r| r.as_ref().ok())
-                .filter(|l| l.has_script() && l.script() == family.primary_script())
-                .reduce(|acc, e| {
-                    if acc.population() > e.population() {
-                        acc
-                    } else {
-                        e
-                    }
-                });
-            if let Some(lang) = lang {
-                primary_language = Some(lang);
-            } else {
-                eprintln!(
-                    "{} specifies a primary_script that matches no languages {}",
-                    family.name(),
-                    family.primary_script()
-                );
-            }
+    /// Case and whitespace are ignored.
+    pub fn family_by_name(&self, name: &str) -> Option<(&Path, &FamilyProto)> {
+        self.name_index().exact(name).map(|i| self.family_at(i))
+    }
+
+    /// Families with a name, display name, directory slug or alias within max_distance edits of name
+    ///
+    /// Case and whitespace are ignored. Results are sorted by ascending edit distance, which is
+    /// returned alongside each family.
+    pub fn families_by_fuzzy_name(
+        &self,
+        name: &str,
+        max_distance: usize,
+    ) -> Vec<(&Path, &FamilyProto, usize)> {
+        self.name_index()
+            .fuzzy(name, max_distance)
+            .into_iter()
+            .map(|(i, distance)| {
+                let (p, f) = self.family_at(i);
+                (p, f, distance)
+            })
+            .collect()
+    }
+
+    /// The binary for font, which lives alongside the METADATA.pb of its family
+    pub fn find_font_binary(&self, font: &FontProto) -> Result<PathBuf, GfMetadataError> {
+        let missing = || GfMetadataError::MissingFontBinary(font.filename().to_string());
+        let (family_path, _) = self.family(font).ok_or_else(missing)?;
+        let dir = family_path.parent().ok_or_else(missing)?;
+        if let Some(e) = missing_dir(dir) {
+            return Err(e);
         }
-        if primary_language.is_none() {
-            primary_language = self.language("en_Latn");
+        let font_file = dir.join(font.filename());
+        if !font_file.is_file() {
+            return Err(missing());
         }
-        primary_language
-            .unwrap_or_else(|| panic!("Not even our final fallback worked for {}", family.name()))
+        Ok(font_file)
+    }
+
+    /// Which languages the binary for font supports, judged by comparing its cmap to exemplars
+    ///
+    /// Languages without base exemplar characters are omitted, see [`language_coverage`].
+    pub fn language_support(
+        &self,
+        font: &FontProto,
+    ) -> Result<Vec<LanguageCoverage<'_>>, GfMetadataError> {
+        let font_file = self.find_font_binary(font)?;
+        let data = fs::read(&font_file).map_err(|e| GfMetadataError::io(&font_file, e))?;
+        let font_ref = FontRef::new(&data).map_err(|error| GfMetadataError::Font {
+            path: font_file.clone(),
+            error,
+        })?;
+        let charmap = font_ref.charmap();
+        Ok(self
+            .languages()
+            .iter()
+            .filter_map(|l| l.as_ref().ok())
+            .filter_map(|l| language_coverage(l, |c| charmap.map(c).is_some()))
+            .collect())
+    }
+
+    /// Check family, read from metadata_file, against its font binaries and gflanguages
+    ///
+    /// See [`RULES`] for what we check.
+    pub fn validate_family(&self, metadata_file: &Path, family: &FamilyProto) -> Vec<Finding> {
+        validate::validate_family(self, metadata_file, family)
+    }
+
+    /// Check every family, see [`Self::validate_family`]
+    pub fn validate(&self) -> ValidationReport {
+        let families = self
+            .families()
+            .par_iter()
+            .map(|(path, family)| match family {
+                Ok(family) => FamilyReport {
+                    metadata_file: path.clone(),
+                    family: Some(family.name().to_string()),
+                    findings: self.validate_family(path, family),
+                },
+                Err(e) => FamilyReport {
+                    metadata_file: path.clone(),
+                    family: None,
+                    findings: vec![Finding {
+                        rule: &validate::METADATA_PARSES,
+                        font: None,
+                        message: e.to_string(),
+                    }],
+                },
+            })
+            .collect();
+        ValidationReport { families }
+    }
+
+    /// What changed in this snapshot of the repository relative to before, an older snapshot
+    ///
+    /// Exemplars, which determine which images are stale, are chosen by exemplar_policy. Only
+    /// families that load successfully are compared. Changes to font binaries that aren't
+    /// reflected in METADATA.pb are not detected.
+    pub fn changes_since(
+        &self,
+        before: &GoogleFonts,
+        exemplar_policy: &dyn ExemplarPolicy,
+    ) -> SnapshotDiff {
+        diff::diff(before, self, exemplar_policy)
+    }
+
+    /// Our best guess at the primary language for this family, and how we arrived at it
+    ///
+    /// Meant to be a good choice for things like rendering a sample string. We try, in order,
+    /// primary_language, primary_script, the languages list and subsets before falling back
+    /// to [`FALLBACK_LANGUAGE`]. Fails only if even that doesn't exist.
+    pub fn primary_language(
+        &self,
+        family: &FamilyProto,
+    ) -> Result<PrimaryLanguage<'_>, GfMetadataError> {
+        primary_language::primary_language(self, family)
     }
 }
 
@@ -405,24 +697,7 @@ mod tests {
     use std::fs;
 
     use super::*;
-
-    fn testdata_dir() -> std::path::PathBuf {
-        // cargo test seems to run in the project directory
-        // VSCode test seems to run in the workspace directory
-        // probe for the file we want in hopes of finding it regardless
-
-        ["./resources/testdata", "../resources/testdata"]
-            .iter()
-            .map(std::path::PathBuf::from)
-            .find(|pb| pb.exists())
-            .unwrap()
-    }
-
-    fn testdata_file_content(relative_path: &str) -> String {
-        let mut p = testdata_dir();
-        p.push(relative_path);
-        fs::read_to_string(p).unwrap()
-    }
+    use crate::test_util::{fake_repo, temp_dir, testdata_file_content};
 
     #[test]
     fn roboto_exemplar() {
@@ -459,23 +734,69 @@ mod tests {
     }
 
     #[test]
-    fn parse_tag3() {
-        Tag::from_str("Roboto Slab, /quant/stroke_width_min, 26.31").expect("To parse");
+    fn google_fonts_is_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<GoogleFonts>();
     }
 
     #[test]
-    fn parse_tag4() {
-        Tag::from_str("Roboto Slab, wght@100, /quant/stroke_width_min, 26.31").expect("To parse");
+    fn missing_repo_dir() {
+        let gf = GoogleFonts::new(PathBuf::from("/this/does/not/exist"), None);
+        assert!(matches!(
+            gf.families(),
+            [(_, Err(GfMetadataError::MissingDirectory(..)))]
+        ));
+        assert!(matches!(
+            gf.languages(),
+            [Err(GfMetadataError::MissingDirectory(..))]
+        ));
+        assert!(matches!(
+            gf.tags(),
+            Err(GfMetadataError::MissingDirectory(..))
+        ));
+        assert!(matches!(
+            gf.tag_metadata(),
+            Err(GfMetadataError::MissingDirectory(..))
+        ));
     }
 
     #[test]
-    fn parse_tag_quoted() {
-        Tag::from_str("Georama, \"ital,wght@1,100\", /quant/stroke_width_min, 16.97")
-            .expect("To parse");
+    fn find_font_binary() {
+        let root = temp_dir("find_font_binary");
+        fake_repo(&root);
+        let family_dir = root.join("ofl/kosugimaru");
+        fs::create_dir_all(&family_dir).unwrap();
+        fs::write(
+            family_dir.join("METADATA.pb"),
+            testdata_file_content("kosugimaru-metadata.pb"),
+        )
+        .unwrap();
+        let gf = GoogleFonts::new(root.clone(), None);
+        let kosugi = gf.families()[0].1.as_ref().unwrap().fonts[0].clone();
+
+        assert!(matches!(
+            gf.find_font_binary(&kosugi),
+            Err(GfMetadataError::MissingFontBinary(f)) if f == "KosugiMaru-Regular.ttf"
+        ));
+        fs::write(family_dir.join(kosugi.filename()), "").unwrap();
+        assert_eq!(
+            family_dir.join("KosugiMaru-Regular.ttf"),
+            gf.find_font_binary(&kosugi).unwrap()
+        );
+
+        let mut stranger = kosugi.clone();
+        stranger.set_filename("Stranger-Regular.ttf".to_string());
+        assert!(matches!(
+            gf.find_font_binary(&stranger),
+            Err(GfMetadataError::MissingFontBinary(..))
+        ));
+        fs::remove_dir_all(&root).unwrap();
     }
 
     #[test]
-    fn parse_tag_quoted2() {
-        Tag::from_str("\"\",t,1").expect("To parse");
+    fn parse_region_and_script() {
+        let region = read_region("id: \"JP\"\nname: \"Japan\"\npopulation: 125000000\n").unwrap();
+        let script = read_script("id: \"Jpan\"\nname: \"Japanese\"\n").unwrap();
+        assert_eq!(("Japan", "Japanese"), (region.name(), script.name()));
     }
 }
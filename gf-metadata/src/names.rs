@@ -0,0 +1,162 @@
+use std::{collections::HashMap, path::Path};
+
+use crate::{FamilyProto, GfMetadataError};
+
+/// Lowercase and drop whitespace so "Noto Sans TC", "noto sans tc" and "NotoSansTC" agree
+pub(crate) fn normalize_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Levenshtein distance, counted in chars
+pub(crate) fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut curr = vec![0; b.len() + 1];
+    for (i, ca) in a.chars().enumerate() {
+        curr[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = prev[j] + usize::from(ca != *cb);
+            curr[j + 1] = substitution.min(prev[j + 1] + 1).min(curr[j] + 1);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+    prev[b.len()]
+}
+
+/// Maps normalized family names, display names, directory slugs and aliases to family indices.
+pub(crate) struct NameIndex {
+    by_name: HashMap<String, usize>,
+}
+
+impl NameIndex {
+    pub(crate) fn new(
+        repo_dir: &Path,
+        families: &[(impl AsRef<Path>, Result<FamilyProto, GfMetadataError>)],
+    ) -> Self {
+        let mut by_name = HashMap::new();
+        let families = families
+            .iter()
+            .enumerate()
+            .filter_map(|(i, (p, f))| f.as_ref().ok().map(|f| (i, p.as_ref(), f)))
+            .collect::<Vec<_>>();
+
+        // Insert in order of precedence, if there is a collision the first entry wins
+        for (i, _, family) in families.iter() {
+            by_name.entry(normalize_name(family.name())).or_insert(*i);
+        }
+        for (i, _, family) in families.iter().filter(|(_, _, f)| f.has_display_name()) {
+            by_name
+                .entry(normalize_name(family.display_name()))
+                .or_insert(*i);
+        }
+        for (i, path, _) in families.iter() {
+            if let Some(slug) = slug(repo_dir, path) {
+                by_name.entry(normalize_name(&slug)).or_insert(*i);
+            }
+        }
+        for (i, _, family) in families.iter() {
+            for alias in family.aliases.iter() {
+                by_name.entry(normalize_name(alias)).or_insert(*i);
+            }
+        }
+        Self { by_name }
+    }
+
+    pub(crate) fn exact(&self, name: &str) -> Option<usize> {
+        self.by_name.get(&normalize_name(name)).copied()
+    }
+
+    /// (family index, edit distance) for every family with a name within max_distance of name
+    ///
+    /// Sorted by ascending distance.
+    pub(crate) fn fuzzy(&self, name: &str, max_distance: usize) -> Vec<(usize, usize)> {
+        let name = normalize_name(name);
+        let mut best: HashMap<usize, usize> = HashMap::new();
+        for (candidate, i) in self.by_name.iter() {
+            let distance = edit_distance(&name, candidate);
+            if distance > max_distance {
+                continue;
+            }
+            let entry = best.entry(*i).or_insert(distance);
+            *entry = (*entry).min(distance);
+        }
+        let mut matches: Vec<_> = best.into_iter().collect();
+        matches.sort_by_key(|(i, distance)| (*distance, *i));
+        matches
+    }
+}
+
+/// The directory of a METADATA.pb relative to the repo, e.g. ofl/notosanstc
+fn slug(repo_dir: &Path, metadata_file: &Path) -> Option<String> {
+    let dir = metadata_file.parent()?.strip_prefix(repo_dir).ok()?;
+    let parts = dir
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>();
+    (!parts.is_empty()).then(|| parts.join("/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::{read_family, test_util::testdata_file_content};
+
+    #[test]
+    fn edit_distances() {
+        assert_eq!(
+            (0, 1, 3, 4),
+            (
+                edit_distance("roboto", "roboto"),
+                edit_distance("robotto", "roboto"),
+                edit_distance("kitten", "sitting"),
+                edit_distance("", "jua!"),
+            )
+        );
+    }
+
+    fn name_index() -> NameIndex {
+        let families = ["roboto", "kosugimaru", "wixmadefortext"]
+            .iter()
+            .map(|n| {
+                let mut family =
+                    read_family(&testdata_file_content(&format!("{n}-metadata.pb"))).unwrap();
+                if *n == "roboto" {
+                    family.aliases.push("Roboto 2".to_string());
+                }
+                (
+                    PathBuf::from(format!("/repo/ofl/{n}/METADATA.pb")),
+                    Ok(family),
+                )
+            })
+            .collect::<Vec<_>>();
+        NameIndex::new(Path::new("/repo"), &families)
+    }
+
+    #[test]
+    fn exact_name_lookup() {
+        let index = name_index();
+        assert_eq!(
+            (Some(0), Some(1), Some(2), Some(0), None),
+            (
+                index.exact("roboto"),
+                index.exact("KosugiMaru"),
+                index.exact("ofl/wixmadefortext"),
+                index.exact(" roboto 2 "),
+                index.exact("ofl/wix"),
+            )
+        );
+    }
+
+    #[test]
+    fn fuzzy_name_lookup() {
+        let index = name_index();
+        assert_eq!(vec![(1, 1)], index.fuzzy("Kosugi Mar", 2));
+        assert_eq!(vec![(0, 0)], index.fuzzy("Roboto", 1));
+        assert!(index.fuzzy("Lobster", 2).is_empty());
+    }
+}
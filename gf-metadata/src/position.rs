@@ -0,0 +1,275 @@
+//! Support for the undocumented `position` field of fonts in google/fonts METADATA.pb.
+//!
+//! Some families list the designspace location of each static font, e.g.
+//!
+//! ```text
+//! fonts {
+//!   name: "Wix Madefor Text"
+//!   ...
+//!   position {
+//!     tag: "wght"
+//!     position: 400.0
+//!   }
+//! }
+//! ```
+//!
+//! `position` isn't in fonts_public.proto so [`FontProto`] has nowhere to put it. We parse with
+//! a copy of fonts_public.proto extended to include it and keep the positions as an unknown
+//! field of each [`FontProto`], where they survive cloning, binary encoding and writing.
+
+use std::sync::OnceLock;
+
+use protobuf::{
+    CodedOutputStream, Message, MessageDyn, UnknownValueRef,
+    descriptor::{
+        DescriptorProto, FieldDescriptorProto,
+        field_descriptor_proto::{Label, Type},
+    },
+    reflect::{FileDescriptor, MessageDescriptor, ReflectValueBox},
+};
+
+use crate::{FamilyProto, FontProto, GfMetadataError, fonts_public, text_format};
+
+/// The field number position has in our extended FontProto, well clear of fonts_public.proto's
+const POSITION_FIELD_NUMBER: u32 = 100;
+
+/// The location of a static font on one axis of its family's designspace
+#[derive(Clone, Debug, PartialEq)]
+pub struct FontPosition {
+    pub tag: String,
+    pub position: f32,
+}
+
+fn field(name: &str, number: u32, label: Label, field_type: Type) -> FieldDescriptorProto {
+    let mut field = FieldDescriptorProto::new();
+    field.set_name(name.to_string());
+    field.set_json_name(name.to_string());
+    field.set_number(number as i32);
+    field.set_label(label);
+    field.set_type(field_type);
+    field
+}
+
+/// fonts_public.proto with FontProto extended by `repeated FontPositionProto position`
+fn extended_fonts_public() -> &'static FileDescriptor {
+    static EXTENDED: OnceLock<FileDescriptor> = OnceLock::new();
+    EXTENDED.get_or_init(|| {
+        let mut file = fonts_public::file_descriptor().proto().clone();
+
+        let mut position = DescriptorProto::new();
+        position.set_name("FontPositionProto".to_string());
+        position
+            .field
+            .push(field("tag", 1, Label::LABEL_OPTIONAL, Type::TYPE_STRING));
+        position.field.push(field(
+            "position",
+            2,
+            Label::LABEL_OPTIONAL,
+            Type::TYPE_FLOAT,
+        ));
+        file.message_type.push(position);
+
+        let mut font_position = field(
+            "position",
+            POSITION_FIELD_NUMBER,
+            Label::LABEL_REPEATED,
+            Type::TYPE_MESSAGE,
+        );
+        font_position.set_type_name(".google.fonts_public.FontPositionProto".to_string());
+        file.message_type
+            .iter_mut()
+            .find(|m| m.name() == "FontProto")
+            .expect("fonts_public.proto has FontProto")
+            .field
+            .push(font_position);
+
+        FileDescriptor::new_dynamic(file, &[]).expect("Valid extension of fonts_public.proto")
+    })
+}
+
+fn extended_message(name: &str) -> MessageDescriptor {
+    extended_fonts_public()
+        .message_by_package_relative_name(name)
+        .unwrap_or_else(|| panic!("Extended fonts_public.proto has no {name}"))
+}
+
+/// Encode without checking required fields are set, we may be asked to write partial protos
+fn to_bytes(message: &dyn MessageDyn) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    message.compute_size_dyn();
+    let mut os = CodedOutputStream::vec(&mut bytes);
+    message
+        .write_to_with_cached_sizes_dyn(&mut os)
+        .and_then(|_| os.flush())
+        .expect("Writing to a Vec doesn't fail");
+    drop(os);
+    bytes
+}
+
+/// Parse a METADATA.pb whose fonts may have positions
+pub(crate) fn parse_family(s: &str) -> Result<FamilyProto, GfMetadataError> {
+    let mut extended = extended_message("FamilyProto").new_instance();
+    protobuf::text_format::merge_from_str(&mut *extended, s)?;
+    if let Some(field) = text_format::missing_required_field(&*extended) {
+        return Err(GfMetadataError::missing_field(field));
+    }
+
+    let mut family = FamilyProto::new();
+    family
+        .merge_from_bytes(&to_bytes(&*extended))
+        .expect("Extended FamilyProto is compatible with FamilyProto");
+    Ok(family)
+}
+
+/// Whether METADATA.pb gives a position for font, cheaper than [`font_positions`]
+pub(crate) fn has_positions(font: &FontProto) -> bool {
+    font.special_fields
+        .unknown_fields()
+        .get(POSITION_FIELD_NUMBER)
+        .is_some()
+}
+
+/// family as an instance of our extended FamilyProto if any font has positions
+pub(crate) fn extended_family(family: &FamilyProto) -> Option<Box<dyn MessageDyn>> {
+    if !family.fonts.iter().any(has_positions) {
+        return None;
+    }
+    let mut extended = extended_message("FamilyProto").new_instance();
+    extended
+        .merge_from_bytes_dyn(&to_bytes(family))
+        .expect("FamilyProto is compatible with extended FamilyProto");
+    Some(extended)
+}
+
+/// The designspace location of a static font, empty if METADATA.pb doesn't say
+pub fn font_positions(font: &FontProto) -> Vec<FontPosition> {
+    let descriptor = extended_message("FontPositionProto");
+    let tag = descriptor.field_by_name("tag").unwrap();
+    let position = descriptor.field_by_name("position").unwrap();
+    font.special_fields
+        .unknown_fields()
+        .iter()
+        .filter_map(|(number, value)| match value {
+            UnknownValueRef::LengthDelimited(bytes) if number == POSITION_FIELD_NUMBER => {
+                descriptor.parse_from_bytes(bytes).ok()
+            }
+            _ => None,
+        })
+        .map(|m| FontPosition {
+            tag: tag
+                .get_singular_field_or_default(&*m)
+                .to_str()
+                .unwrap_or_default()
+                .to_string(),
+            position: position
+                .get_singular_field_or_default(&*m)
+                .to_f32()
+                .unwrap_or_default(),
+        })
+        .collect()
+}
+
+/// Replace the designspace location of a static font
+pub fn set_font_positions(font: &mut FontProto, positions: &[FontPosition]) {
+    let descriptor = extended_message("FontPositionProto");
+    let tag = descriptor.field_by_name("tag").unwrap();
+    let position = descriptor.field_by_name("position").unwrap();
+    let unknown_fields = font.special_fields.mut_unknown_fields();
+    unknown_fields.remove(POSITION_FIELD_NUMBER);
+    for p in positions {
+        let mut m = descriptor.new_instance();
+        tag.set_singular_field(&mut *m, ReflectValueBox::String(p.tag.clone()));
+        position.set_singular_field(&mut *m, ReflectValueBox::F32(p.position));
+        unknown_fields.add_length_delimited(POSITION_FIELD_NUMBER, to_bytes(&*m));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{read_family, test_util::testdata_file_content, write_family};
+
+    #[test]
+    fn wix_font_positions() {
+        let wix = read_family(&testdata_file_content("wixmadefortext-metadata.pb")).unwrap();
+        let positions = wix
+            .fonts
+            .iter()
+            .map(|f| (f.filename(), font_positions(f)))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                ("WixMadeforText[wght].ttf", vec![]),
+                ("WixMadeforText-Italic[wght].ttf", vec![]),
+                (
+                    "WixMadeforText-Regular.ttf",
+                    vec![FontPosition {
+                        tag: "wght".to_string(),
+                        position: 400.0
+                    }]
+                ),
+            ],
+            positions[..3]
+        );
+        assert_eq!(
+            vec![500.0, 500.0, 600.0, 600.0, 700.0, 700.0, 800.0, 800.0],
+            positions[4..]
+                .iter()
+                .flat_map(|(_, p)| p.iter().map(|p| p.position))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn edit_font_positions() {
+        let mut family = read_family(&testdata_file_content("kosugimaru-metadata.pb")).unwrap();
+        let positions = vec![
+            FontPosition {
+                tag: "wght".to_string(),
+                position: 400.0,
+            },
+            FontPosition {
+                tag: "wdth".to_string(),
+                position: 87.5,
+            },
+        ];
+        set_font_positions(&mut family.fonts[0], &positions);
+        assert_eq!(positions, font_positions(&family.fonts[0]));
+
+        let written = write_family(&family);
+        assert!(
+            written.contains(concat!(
+                "  full_name: \"Kosugi Maru Regular\"\n",
+                "  copyright: \"Copyright 2010 The Kosugi Maru Project Authors (https://github.com/googlefonts/kosugi-maru)\"\n",
+                "  position {\n",
+                "    tag: \"wght\"\n",
+                "    position: 400.0\n",
+                "  }\n",
+                "  position {\n",
+                "    tag: \"wdth\"\n",
+                "    position: 87.5\n",
+                "  }\n",
+                "}\n",
+            )),
+            "{written}"
+        );
+        assert_eq!(family, read_family(&written).unwrap());
+
+        set_font_positions(&mut family.fonts[0], &[]);
+        assert_eq!(
+            testdata_file_content("kosugimaru-metadata.pb") + "\n",
+            write_family(&family)
+        );
+    }
+
+    #[test]
+    fn position_with_missing_required_field() {
+        let wix = testdata_file_content("wixmadefortext-metadata.pb")
+            .replace("  filename: \"WixMadeforText-Regular.ttf\"\n", "");
+        let err = read_family(&wix).unwrap_err();
+        let GfMetadataError::MissingField { field, .. } = err else {
+            panic!("Wrong error type {err:?}");
+        };
+        assert_eq!("fonts[2].filename", field);
+    }
+}
@@ -0,0 +1,304 @@
+//! Deciding the primary language of a family, e.g. to pick sample text.
+
+use std::fmt;
+
+use crate::{FamilyProto, GfMetadataError, GoogleFonts, LanguageProto};
+
+/// The language we use when a family gives us nothing better to go on
+pub const FALLBACK_LANGUAGE: &str = "en_Latn";
+
+/// Subsets whose name isn't that of their script, which is otherwise how we find it
+const SUBSET_SCRIPTS: &[(&str, &str)] = &[
+    ("chinese-simplified", "Hans"),
+    ("chinese-traditional", "Hant"),
+    ("chinese-hongkong", "Hant"),
+];
+
+/// Subsets that tell us nothing about the language of a family
+const IGNORED_SUBSETS: &[&str] = &["menu", "math", "symbols", "symbols2", "emoji", "music"];
+
+/// How the primary language of a family was decided
+#[derive(Clone, Debug, PartialEq)]
+pub enum LanguageSource {
+    /// The family's primary_language
+    Explicit,
+    /// The most populous language written in the family's primary_script
+    PrimaryScript,
+    /// The most populous language in the family's languages list
+    FamilyLanguages,
+    /// Derived from this subset, [`FALLBACK_LANGUAGE`] if it's latin, otherwise the most
+    /// populous language written in the script of the subset
+    Subset(String),
+    /// Nothing told us any better so [`FALLBACK_LANGUAGE`]
+    Fallback,
+}
+
+impl fmt::Display for LanguageSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LanguageSource::Explicit => write!(f, "primary_language"),
+            LanguageSource::PrimaryScript => write!(f, "primary_script"),
+            LanguageSource::FamilyLanguages => write!(f, "languages"),
+            LanguageSource::Subset(subset) => write!(f, "subset {subset}"),
+            LanguageSource::Fallback => write!(f, "fallback"),
+        }
+    }
+}
+
+/// The primary language of a family and how we arrived at it
+#[derive(Clone, Debug, PartialEq)]
+pub struct PrimaryLanguage<'a> {
+    pub language: &'a LanguageProto,
+    pub source: LanguageSource,
+    /// Why earlier sources were passed over, e.g. a primary_language that doesn't exist
+    pub notes: Vec<String>,
+}
+
+fn most_populous<'a>(
+    languages: impl Iterator<Item = &'a LanguageProto>,
+) -> Option<&'a LanguageProto> {
+    languages.reduce(|acc, e| {
+        if acc.population() > e.population() {
+            acc
+        } else {
+            e
+        }
+    })
+}
+
+/// The id of the script of subset, e.g. Cyrl for cyrillic-ext
+fn subset_script<'a>(gf: &'a GoogleFonts, subset: &str) -> Option<&'a str> {
+    if let Some((_, script)) = SUBSET_SCRIPTS.iter().find(|(s, _)| *s == subset) {
+        return gf.script(script).map(|s| s.id());
+    }
+    let name = subset.strip_suffix("-ext").unwrap_or(subset);
+    gf.scripts()
+        .iter()
+        .filter_map(|s| s.as_ref().ok())
+        .find(|s| s.name().to_lowercase().replace(' ', "-") == name)
+        .map(|s| s.id())
+}
+
+/// The most populous language of the first subset we can make sense of
+///
+/// Every latin family supports the latin subset, often alongside many others, so a family
+/// that supports latin gets [`FALLBACK_LANGUAGE`] lest Roboto speak Cyrillic.
+fn from_subsets<'a>(
+    gf: &'a GoogleFonts,
+    family: &FamilyProto,
+    notes: &mut Vec<String>,
+) -> Option<(&'a LanguageProto, String)> {
+    let subsets = family
+        .subsets
+        .iter()
+        .filter(|s| !IGNORED_SUBSETS.contains(&s.as_str()))
+        .collect::<Vec<_>>();
+    if let Some(latin) = subsets.iter().find(|s| s.as_str() == "latin") {
+        return gf
+            .language(FALLBACK_LANGUAGE)
+            .map(|l| (l, latin.to_string()));
+    }
+    for subset in subsets {
+        let Some(script) = subset_script(gf, subset) else {
+            notes.push(format!("subset {subset} matches no script"));
+            continue;
+        };
+        match most_populous(gf.languages_for_script(script)) {
+            Some(lang) => return Some((lang, subset.clone())),
+            None => notes.push(format!("subset {subset} matches no languages")),
+        }
+    }
+    None
+}
+
+pub(crate) fn primary_language<'a>(
+    gf: &'a GoogleFonts,
+    family: &FamilyProto,
+) -> Result<PrimaryLanguage<'a>, GfMetadataError> {
+    let mut notes = Vec::new();
+    let found = |language, source, notes| {
+        Ok(PrimaryLanguage {
+            language,
+            source,
+            notes,
+        })
+    };
+
+    if family.has_primary_language() {
+        match gf.language(family.primary_language()) {
+            Some(lang) => return found(lang, LanguageSource::Explicit, notes),
+            None => notes.push(format!(
+                "invalid primary_language {}",
+                family.primary_language()
+            )),
+        }
+    }
+    if family.has_primary_script() {
+        // If our script matches many languages pick the one with the highest population
+        match most_populous(gf.languages_for_script(family.primary_script())) {
+            Some(lang) => return found(lang, LanguageSource::PrimaryScript, notes),
+            None => notes.push(format!(
+                "primary_script {} matches no languages",
+                family.primary_script()
+            )),
+        }
+    }
+    if !family.languages.is_empty() {
+        match most_populous(family.languages.iter().filter_map(|l| gf.language(l))) {
+            Some(lang) => return found(lang, LanguageSource::FamilyLanguages, notes),
+            None => notes.push(format!(
+                "languages {} match no languages",
+                family.languages.join(", ")
+            )),
+        }
+    }
+    if let Some((lang, subset)) = from_subsets(gf, family, &mut notes) {
+        return found(lang, LanguageSource::Subset(subset), notes);
+    }
+    match gf.language(FALLBACK_LANGUAGE) {
+        Some(lang) => found(lang, LanguageSource::Fallback, notes),
+        None => Err(GfMetadataError::NoLanguage(family.name().to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::Path};
+
+    use super::*;
+    use crate::{
+        read_family,
+        test_util::{fake_repo, temp_dir},
+    };
+
+    /// A repo with a few languages and scripts
+    fn language_repo(root: &Path) -> GoogleFonts {
+        fake_repo(root);
+        let data = root.join("lang/Lib/gflanguages/data");
+        for (id, population) in [
+            ("en_Latn", 1000),
+            ("fr_Latn", 100),
+            ("ru_Cyrl", 500),
+            ("uk_Cyrl", 50),
+            ("ja_Jpan", 800),
+            ("zh_Hans", 2000),
+        ] {
+            let dir = data.join("languages");
+            fs::create_dir_all(&dir).unwrap();
+            let (language, script) = id.split_once('_').unwrap();
+            fs::write(
+                dir.join(format!("{id}.textproto")),
+                format!(
+                    "id: \"{id}\"\nlanguage: \"{language}\"\nscript: \"{script}\"\npopulation: {population}\n"
+                ),
+            )
+            .unwrap();
+        }
+        for (id, name) in [
+            ("Latn", "Latin"),
+            ("Cyrl", "Cyrillic"),
+            ("Jpan", "Japanese"),
+            ("Hans", "Han (Simplified variant)"),
+            ("Grek", "Greek"),
+        ] {
+            let dir = data.join("scripts");
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(
+                dir.join(format!("{id}.textproto")),
+                format!("id: \"{id}\"\nname: \"{name}\"\n"),
+            )
+            .unwrap();
+        }
+        GoogleFonts::new(root.to_path_buf(), None)
+    }
+
+    #[test]
+    fn primary_language_provenance() {
+        let root = temp_dir("primary_language_provenance");
+        let gf = language_repo(&root);
+        let family = |extra: &str| {
+            read_family(&format!(
+                "name: \"Test\"\ndesigner: \"Someone\"\nlicense: \"OFL\"\n\
+                 date_added: \"2025-01-01\"\n{extra}"
+            ))
+            .unwrap()
+        };
+        let primary = |extra: &str| {
+            let primary = gf.primary_language(&family(extra)).unwrap();
+            (
+                primary.language.id().to_string(),
+                primary.source.to_string(),
+                primary.notes,
+            )
+        };
+        let no_notes = Vec::<String>::new();
+
+        assert_eq!(
+            (
+                "fr_Latn".to_string(),
+                "primary_language".to_string(),
+                no_notes.clone()
+            ),
+            primary("primary_language: \"fr_Latn\"\n")
+        );
+        assert_eq!(
+            (
+                "ja_Jpan".to_string(),
+                "primary_script".to_string(),
+                vec!["invalid primary_language Invalid".to_string()]
+            ),
+            primary("primary_script: \"Jpan\"\nprimary_language: \"Invalid\"\n")
+        );
+        assert_eq!(
+            (
+                "ru_Cyrl".to_string(),
+                "languages".to_string(),
+                no_notes.clone()
+            ),
+            primary("languages: \"uk_Cyrl\"\nlanguages: \"ru_Cyrl\"\n")
+        );
+        // latin wins over cyrillic
+        assert_eq!(
+            (
+                "en_Latn".to_string(),
+                "subset latin".to_string(),
+                no_notes.clone()
+            ),
+            primary("subsets: \"cyrillic\"\nsubsets: \"latin\"\nsubsets: \"menu\"\n")
+        );
+        assert_eq!(
+            (
+                "ru_Cyrl".to_string(),
+                "subset cyrillic-ext".to_string(),
+                no_notes.clone()
+            ),
+            primary("subsets: \"cyrillic-ext\"\nsubsets: \"menu\"\n")
+        );
+        assert_eq!(
+            (
+                "zh_Hans".to_string(),
+                "subset chinese-simplified".to_string(),
+                no_notes.clone()
+            ),
+            primary("subsets: \"chinese-simplified\"\n")
+        );
+        assert_eq!(
+            (
+                "en_Latn".to_string(),
+                "fallback".to_string(),
+                vec![
+                    "subset greek matches no languages".to_string(),
+                    "subset klingon matches no script".to_string()
+                ]
+            ),
+            primary("subsets: \"greek\"\nsubsets: \"klingon\"\n")
+        );
+
+        let empty = GoogleFonts::new(root.join("ofl"), None);
+        assert!(matches!(
+            empty.primary_language(&family("")),
+            Err(GfMetadataError::NoLanguage(name)) if name == "Test"
+        ));
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
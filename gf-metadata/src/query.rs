@@ -0,0 +1,209 @@
+use crate::FamilyProto;
+
+#[derive(Clone, Debug, PartialEq)]
+enum Criterion {
+    Category(String),
+    Subset(String),
+    Axis {
+        tag: String,
+        min: Option<f32>,
+        max: Option<f32>,
+    },
+    Variable(bool),
+    License(String),
+    Designer(String),
+    AddedSince(String),
+    AddedBefore(String),
+    IsNoto(bool),
+    Stroke(String),
+    Classification(String),
+    PrimaryScript(String),
+}
+
+impl Criterion {
+    fn matches(&self, family: &FamilyProto) -> bool {
+        match self {
+            Criterion::Category(c) => family.category.iter().any(|e| e.eq_ignore_ascii_case(c)),
+            Criterion::Subset(s) => family.subsets.iter().any(|e| e.eq_ignore_ascii_case(s)),
+            Criterion::Axis { tag, min, max } => family.axes.iter().any(|a| {
+                a.tag() == tag
+                    && min.map(|v| a.min_value() <= v).unwrap_or(true)
+                    && max.map(|v| a.max_value() >= v).unwrap_or(true)
+            }),
+            Criterion::Variable(v) => family.axes.is_empty() != *v,
+            Criterion::License(l) => family.license().eq_ignore_ascii_case(l),
+            Criterion::Designer(d) => family.designer().to_lowercase().contains(&d.to_lowercase()),
+            // ISO 8601 dates compare correctly as strings
+            Criterion::AddedSince(d) => {
+                family.has_date_added() && family.date_added() >= d.as_str()
+            }
+            Criterion::AddedBefore(d) => {
+                family.has_date_added() && family.date_added() < d.as_str()
+            }
+            Criterion::IsNoto(v) => family.is_noto() == *v,
+            Criterion::Stroke(s) => family.has_stroke() && family.stroke().eq_ignore_ascii_case(s),
+            Criterion::Classification(c) => family
+                .classifications
+                .iter()
+                .any(|e| e.eq_ignore_ascii_case(c)),
+            Criterion::PrimaryScript(s) => {
+                family.has_primary_script() && family.primary_script() == s
+            }
+        }
+    }
+}
+
+/// A composable filter over [`FamilyProto`] fields.
+///
+/// Every criterion added must match for a family to match; an empty query matches everything.
+/// Textual comparisons are case-insensitive except for axis tags and scripts, which are
+/// case-sensitive by definition.
+///
+/// ```
+/// use gf_metadata::FamilyQuery;
+///
+/// // Variable serif fonts with a wdth axis added since the start of 2020
+/// let query = FamilyQuery::new()
+///     .category("SERIF")
+///     .variable(true)
+///     .axis("wdth")
+///     .added_since("2020-01-01");
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FamilyQuery {
+    criteria: Vec<Criterion>,
+}
+
+impl FamilyQuery {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    fn with(mut self, criterion: Criterion) -> Self {
+        self.criteria.push(criterion);
+        self
+    }
+
+    /// Require the family to list this category, e.g. SERIF or HANDWRITING
+    pub fn category(self, category: &str) -> Self {
+        self.with(Criterion::Category(category.to_string()))
+    }
+
+    /// Require the family to list this subset, e.g. latin or cyrillic-ext
+    pub fn subset(self, subset: &str) -> Self {
+        self.with(Criterion::Subset(subset.to_string()))
+    }
+
+    /// Require the family to have an axis with this tag
+    pub fn axis(self, tag: &str) -> Self {
+        self.with(Criterion::Axis {
+            tag: tag.to_string(),
+            min: None,
+            max: None,
+        })
+    }
+
+    /// Require the family to have an axis with this tag that spans at least min..=max
+    pub fn axis_range(self, tag: &str, min: f32, max: f32) -> Self {
+        self.with(Criterion::Axis {
+            tag: tag.to_string(),
+            min: Some(min),
+            max: Some(max),
+        })
+    }
+
+    /// Require the family to be variable, that is to declare axes, or not
+    pub fn variable(self, variable: bool) -> Self {
+        self.with(Criterion::Variable(variable))
+    }
+
+    /// Require the family to use this license, e.g. OFL
+    pub fn license(self, license: &str) -> Self {
+        self.with(Criterion::License(license.to_string()))
+    }
+
+    /// Require the designer field to contain this text
+    pub fn designer(self, designer: &str) -> Self {
+        self.with(Criterion::Designer(designer.to_string()))
+    }
+
+    /// Require the family to have been added on or after this date
+    ///
+    /// Dates compare as text so a partial date such as "2020" is permitted.
+    pub fn added_since(self, date: &str) -> Self {
+        self.with(Criterion::AddedSince(date.to_string()))
+    }
+
+    /// Require the family to have been added strictly before this date
+    pub fn added_before(self, date: &str) -> Self {
+        self.with(Criterion::AddedBefore(date.to_string()))
+    }
+
+    /// Require the family to be, or not be, part of Noto
+    pub fn is_noto(self, is_noto: bool) -> Self {
+        self.with(Criterion::IsNoto(is_noto))
+    }
+
+    /// Require the family to have this stroke, e.g. SANS_SERIF
+    pub fn stroke(self, stroke: &str) -> Self {
+        self.with(Criterion::Stroke(stroke.to_string()))
+    }
+
+    /// Require the family to list this classification, e.g. DISPLAY
+    pub fn classification(self, classification: &str) -> Self {
+        self.with(Criterion::Classification(classification.to_string()))
+    }
+
+    /// Require the family to have this primary script, e.g. Jpan
+    pub fn primary_script(self, script: &str) -> Self {
+        self.with(Criterion::PrimaryScript(script.to_string()))
+    }
+
+    pub fn matches(&self, family: &FamilyProto) -> bool {
+        self.criteria.iter().all(|c| c.matches(family))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{read_family, test_util::testdata_file_content};
+
+    #[test]
+    fn query_category_and_axes() {
+        let roboto = read_family(&testdata_file_content("roboto-metadata.pb")).unwrap();
+        let kosugi = read_family(&testdata_file_content("kosugimaru-metadata.pb")).unwrap();
+        let query = FamilyQuery::new()
+            .category("sans_serif")
+            .variable(true)
+            .axis_range("wght", 200.0, 800.0);
+        assert_eq!(
+            (true, false),
+            (query.matches(&roboto), query.matches(&kosugi))
+        );
+        assert!(
+            !FamilyQuery::new()
+                .axis_range("wdth", 50.0, 100.0)
+                .matches(&roboto)
+        );
+    }
+
+    #[test]
+    fn query_dates_and_scripts() {
+        let roboto = read_family(&testdata_file_content("roboto-metadata.pb")).unwrap();
+        let kosugi = read_family(&testdata_file_content("kosugimaru-metadata.pb")).unwrap();
+        let query = FamilyQuery::new()
+            .added_since("2020")
+            .license("apache2")
+            .primary_script("Jpan")
+            .subset("japanese")
+            .designer("motoya")
+            .is_noto(false);
+        assert_eq!(
+            (false, true),
+            (query.matches(&roboto), query.matches(&kosugi))
+        );
+        assert!(FamilyQuery::new().added_before("2014").matches(&roboto));
+        assert!(FamilyQuery::new().matches(&roboto));
+    }
+}
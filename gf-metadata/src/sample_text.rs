@@ -0,0 +1,381 @@
+//! Choosing sample text for a family, e.g. to render a test image.
+
+use std::{fmt, str::FromStr};
+
+use crate::{FamilyProto, GoogleFonts, LanguageProto, fonts_public, languages_public};
+
+/// The sample text fields shared by families and languages
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SampleTextField {
+    MastheadFull,
+    MastheadPartial,
+    Styles,
+    Tester,
+    PosterSm,
+    PosterMd,
+    PosterLg,
+}
+
+impl SampleTextField {
+    pub const ALL: [SampleTextField; 7] = [
+        SampleTextField::MastheadFull,
+        SampleTextField::MastheadPartial,
+        SampleTextField::Styles,
+        SampleTextField::Tester,
+        SampleTextField::PosterSm,
+        SampleTextField::PosterMd,
+        SampleTextField::PosterLg,
+    ];
+
+    /// The name of the field in the protos
+    pub fn name(&self) -> &'static str {
+        match self {
+            SampleTextField::MastheadFull => "masthead_full",
+            SampleTextField::MastheadPartial => "masthead_partial",
+            SampleTextField::Styles => "styles",
+            SampleTextField::Tester => "tester",
+            SampleTextField::PosterSm => "poster_sm",
+            SampleTextField::PosterMd => "poster_md",
+            SampleTextField::PosterLg => "poster_lg",
+        }
+    }
+}
+
+impl fmt::Display for SampleTextField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Families and languages each have their own SampleTextProto with the same fields
+trait SampleTextFields {
+    fn field(&self, field: SampleTextField) -> &str;
+}
+
+impl SampleTextFields for fonts_public::SampleTextProto {
+    fn field(&self, field: SampleTextField) -> &str {
+        match field {
+            SampleTextField::MastheadFull => self.masthead_full(),
+            SampleTextField::MastheadPartial => self.masthead_partial(),
+            SampleTextField::Styles => self.styles(),
+            SampleTextField::Tester => self.tester(),
+            SampleTextField::PosterSm => self.poster_sm(),
+            SampleTextField::PosterMd => self.poster_md(),
+            SampleTextField::PosterLg => self.poster_lg(),
+        }
+    }
+}
+
+impl SampleTextFields for languages_public::SampleTextProto {
+    fn field(&self, field: SampleTextField) -> &str {
+        match field {
+            SampleTextField::MastheadFull => self.masthead_full(),
+            SampleTextField::MastheadPartial => self.masthead_partial(),
+            SampleTextField::Styles => self.styles(),
+            SampleTextField::Tester => self.tester(),
+            SampleTextField::PosterSm => self.poster_sm(),
+            SampleTextField::PosterMd => self.poster_md(),
+            SampleTextField::PosterLg => self.poster_lg(),
+        }
+    }
+}
+
+/// What sample text will be used for, each purpose prefers different fields
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum SampleTextPurpose {
+    /// A short headline, masthead_full then masthead_partial
+    Masthead,
+    /// A line showing off a style, styles
+    #[default]
+    Styles,
+    /// A paragraph, tester
+    Paragraph,
+    /// Large display text, poster_lg, poster_md then poster_sm
+    Poster,
+}
+
+impl SampleTextPurpose {
+    /// Fields suitable for this purpose, most preferred first
+    pub fn fields(&self) -> &'static [SampleTextField] {
+        match self {
+            SampleTextPurpose::Masthead => &[
+                SampleTextField::MastheadFull,
+                SampleTextField::MastheadPartial,
+            ],
+            SampleTextPurpose::Styles => &[SampleTextField::Styles],
+            SampleTextPurpose::Paragraph => &[SampleTextField::Tester],
+            SampleTextPurpose::Poster => &[
+                SampleTextField::PosterLg,
+                SampleTextField::PosterMd,
+                SampleTextField::PosterSm,
+            ],
+        }
+    }
+}
+
+impl FromStr for SampleTextPurpose {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "masthead" => Ok(SampleTextPurpose::Masthead),
+            "styles" => Ok(SampleTextPurpose::Styles),
+            "paragraph" => Ok(SampleTextPurpose::Paragraph),
+            "poster" => Ok(SampleTextPurpose::Poster),
+            _ => Err(format!(
+                "Unknown sample text purpose {s:?}, expected one of masthead, styles, paragraph \
+                 or poster"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for SampleTextPurpose {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SampleTextPurpose::Masthead => write!(f, "masthead"),
+            SampleTextPurpose::Styles => write!(f, "styles"),
+            SampleTextPurpose::Paragraph => write!(f, "paragraph"),
+            SampleTextPurpose::Poster => write!(f, "poster"),
+        }
+    }
+}
+
+/// Where sample text came from
+#[derive(Clone, Debug, PartialEq)]
+pub enum SampleTextSource {
+    /// The family's own sample_text in METADATA.pb
+    Family,
+    /// The sample_text of the language with this id
+    Language(String),
+}
+
+impl fmt::Display for SampleTextSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SampleTextSource::Family => write!(f, "family"),
+            SampleTextSource::Language(id) => write!(f, "language {id}"),
+        }
+    }
+}
+
+/// Sample text along with where it came from
+#[derive(Clone, Debug, PartialEq)]
+pub struct SampleText {
+    pub text: String,
+    pub field: SampleTextField,
+    pub source: SampleTextSource,
+    /// Whether text was cut short to fit the character budget
+    pub truncated: bool,
+}
+
+/// The first max_chars characters of text, preferably ending at a word boundary
+fn truncate(text: &str, max_chars: usize) -> &str {
+    let Some((end, _)) = text.char_indices().nth(max_chars) else {
+        return text;
+    };
+    let text = &text[..end];
+    match text.rfind(char::is_whitespace) {
+        Some(space) if space > 0 => text[..space].trim_end(),
+        _ => text,
+    }
+}
+
+/// Picks sample text for a family by purpose and approximate length.
+///
+/// The family's own sample text is honored first, then that of its primary language, see
+/// [`GoogleFonts::primary_language`]. Within each source the longest text for the purpose
+/// that fits the character budget wins. If nothing fits the most preferred text is truncated.
+/// If neither source has text for the purpose, any field will do.
+///
+/// ```
+/// use gf_metadata::{SampleTextPurpose, SampleTextSelector};
+///
+/// // A headline of no more than 20 characters
+/// let selector = SampleTextSelector::new(SampleTextPurpose::Masthead).max_chars(20);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SampleTextSelector {
+    purpose: SampleTextPurpose,
+    max_chars: Option<usize>,
+}
+
+impl SampleTextSelector {
+    pub fn new(purpose: SampleTextPurpose) -> Self {
+        Self {
+            purpose,
+            max_chars: None,
+        }
+    }
+
+    /// Prefer text of at most this many characters
+    pub fn max_chars(mut self, max_chars: usize) -> Self {
+        self.max_chars = Some(max_chars);
+        self
+    }
+
+    /// Sample text for family, None if neither it nor its primary language has any
+    pub fn select(&self, gf: &GoogleFonts, family: &FamilyProto) -> Option<SampleText> {
+        let language = gf.primary_language(family).ok().map(|p| p.language);
+        self.select_from(family, language)
+    }
+
+    /// Sample text from family or, failing that, language
+    pub fn select_from(
+        &self,
+        family: &FamilyProto,
+        language: Option<&LanguageProto>,
+    ) -> Option<SampleText> {
+        let mut sources: Vec<(SampleTextSource, &dyn SampleTextFields)> = Vec::new();
+        if let Some(sample_text) = family.sample_text.as_ref() {
+            sources.push((SampleTextSource::Family, sample_text));
+        }
+        if let Some(sample_text) = language.and_then(|l| l.sample_text.as_ref()) {
+            let id = language.map(|l| l.id()).unwrap_or_default();
+            sources.push((SampleTextSource::Language(id.to_string()), sample_text));
+        }
+
+        [self.purpose.fields(), &SampleTextField::ALL]
+            .into_iter()
+            .flat_map(|fields| sources.iter().map(move |source| (fields, source)))
+            .find_map(|(fields, (source, sample_text))| {
+                self.select_field(fields, source, *sample_text)
+            })
+    }
+
+    fn select_field(
+        &self,
+        fields: &[SampleTextField],
+        source: &SampleTextSource,
+        sample_text: &dyn SampleTextFields,
+    ) -> Option<SampleText> {
+        let candidates = fields
+            .iter()
+            .map(|f| (*f, sample_text.field(*f)))
+            .filter(|(_, text)| !text.trim().is_empty())
+            .collect::<Vec<_>>();
+        let max_chars = self.max_chars.unwrap_or(usize::MAX);
+        let fits = candidates
+            .iter()
+            .filter(|(_, text)| text.chars().count() <= max_chars)
+            .max_by_key(|(_, text)| text.chars().count());
+        let (field, text, truncated) = match fits {
+            Some((field, text)) => (*field, *text, false),
+            None => {
+                let (field, text) = candidates.first()?;
+                (*field, truncate(text, max_chars), true)
+            }
+        };
+        Some(SampleText {
+            text: text.to_string(),
+            field,
+            source: source.clone(),
+            truncated,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{read_family, read_language};
+
+    #[test]
+    fn sample_text_selection() {
+        let family = read_family(
+            r#"name: "Test"
+designer: "Someone"
+license: "OFL"
+date_added: "2025-01-01"
+sample_text {
+  styles: "Family styles"
+}
+"#,
+        )
+        .unwrap();
+        let language = read_language(
+            r#"id: "xx_Latn"
+sample_text {
+  styles: "Language styles"
+  tester: "The quick brown fox jumps over the lazy dog"
+  poster_sm: "A smaller poster"
+  poster_md: "Medium text"
+  poster_lg: "Big"
+}
+"#,
+        )
+        .unwrap();
+        let select = |selector: SampleTextSelector| {
+            let s = selector.select_from(&family, Some(&language)).unwrap();
+            (s.text, s.field.name(), s.source.to_string(), s.truncated)
+        };
+        let purpose = SampleTextSelector::new;
+
+        assert_eq!(
+            (
+                "Family styles".to_string(),
+                "styles",
+                "family".to_string(),
+                false
+            ),
+            select(purpose(SampleTextPurpose::Styles))
+        );
+        assert_eq!(
+            (
+                "The quick brown fox jumps over the lazy dog".to_string(),
+                "tester",
+                "language xx_Latn".to_string(),
+                false
+            ),
+            select(purpose(SampleTextPurpose::Paragraph))
+        );
+        assert_eq!(
+            (
+                "The quick".to_string(),
+                "tester",
+                "language xx_Latn".to_string(),
+                true
+            ),
+            select(purpose(SampleTextPurpose::Paragraph).max_chars(12))
+        );
+        assert_eq!(
+            (
+                "Medium text".to_string(),
+                "poster_md",
+                "language xx_Latn".to_string(),
+                false
+            ),
+            select(purpose(SampleTextPurpose::Poster).max_chars(12))
+        );
+        assert_eq!(
+            (
+                "Bi".to_string(),
+                "poster_lg",
+                "language xx_Latn".to_string(),
+                true
+            ),
+            select(purpose(SampleTextPurpose::Poster).max_chars(2))
+        );
+        // Nobody has a masthead so we settle for whatever the family has
+        assert_eq!(
+            (
+                "Family styles".to_string(),
+                "styles",
+                "family".to_string(),
+                false
+            ),
+            select(purpose(SampleTextPurpose::Masthead))
+        );
+
+        let mut bare = family.clone();
+        bare.sample_text.clear();
+        assert_eq!(
+            None,
+            purpose(SampleTextPurpose::Styles).select_from(&bare, None)
+        );
+        assert_eq!(
+            Ok(SampleTextPurpose::Poster),
+            SampleTextPurpose::from_str("poster")
+        );
+    }
+}
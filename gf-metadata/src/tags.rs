@@ -0,0 +1,679 @@
+use std::{
+    collections::HashMap,
+    fmt, fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use crate::{
+    FamilyProto, FontProto, GfMetadataError,
+    csv::{self, CsvRecord},
+    missing_dir,
+};
+
+pub(crate) fn read_csv_file<T: CsvRecord>(file: &Path) -> Result<Vec<T>, GfMetadataError> {
+    let content = fs::read_to_string(file).map_err(|e| GfMetadataError::io(file, e))?;
+    csv::parse_csv(&content).map_err(|e| e.with_path(file))
+}
+
+/// Write tags in the format of the google/fonts tags/all/*.csv files
+pub fn write_tags(tags: &[Tag]) -> String {
+    csv::write_csv(None, tags)
+}
+
+/// Write tag metadata in the format of the google/fonts tags/tags_metadata.csv file
+pub fn write_tag_metadata(metadata: &[TagMetadata]) -> String {
+    csv::write_csv(None, metadata)
+}
+
+/// Update, or insert, tags without disturbing unrelated entries.
+///
+/// An update replaces the value of the entry with the same family, location and tag. If
+/// there is no such entry it is inserted after the last entry for the family, or appended
+/// if the family has no entries.
+pub fn merge_tags(tags: &mut Vec<Tag>, updates: impl IntoIterator<Item = Tag>) {
+    for update in updates {
+        match merge_index(tags, |t| t, &update) {
+            Ok(i) => tags[i].value = update.value,
+            Err(i) => tags.insert(i, update),
+        }
+    }
+}
+
+/// Where [`merge_tags`] puts update: Ok with the entry to replace or Err with where to insert
+fn merge_index<T>(rows: &[T], tag: impl Fn(&T) -> &Tag, update: &Tag) -> Result<usize, usize> {
+    if let Some(i) = rows
+        .iter()
+        .map(&tag)
+        .position(|t| t.family == update.family && t.loc == update.loc && t.tag == update.tag)
+    {
+        return Ok(i);
+    }
+    Err(rows
+        .iter()
+        .map(&tag)
+        .rposition(|t| t.family == update.family)
+        .map(|i| i + 1)
+        .unwrap_or(rows.len()))
+}
+
+/// A line of a tags csv file, with its fields as written if it has not been merged into
+struct TagRow {
+    tag: Tag,
+    fields: Option<Vec<String>>,
+}
+
+impl CsvRecord for TagRow {
+    fn from_record(fields: &[String]) -> Result<Self, GfMetadataError> {
+        Ok(TagRow {
+            tag: Tag::from_record(fields)?,
+            fields: Some(fields.to_vec()),
+        })
+    }
+
+    fn to_record(&self) -> Vec<String> {
+        self.fields.clone().unwrap_or_else(|| self.tag.to_record())
+    }
+
+    fn is_header(fields: &[String]) -> bool {
+        Tag::is_header(fields)
+    }
+}
+
+/// [`merge_tags`] into a tags csv file, preserving its header if it has one
+///
+/// Lines that aren't merged into keep their values as written, e.g. 8.0 stays 8.0, though
+/// their fields may be requoted.
+pub fn merge_tags_file(
+    file: &Path,
+    updates: impl IntoIterator<Item = Tag>,
+) -> Result<(), GfMetadataError> {
+    let content = fs::read_to_string(file).map_err(|e| GfMetadataError::io(file, e))?;
+    let header = csv::parse_records(&content)
+        .map_err(|e| e.with_path(file))?
+        .into_iter()
+        .next()
+        .map(|(_, fields)| fields)
+        .filter(|fields| Tag::is_header(fields));
+    let mut rows: Vec<TagRow> = csv::parse_csv(&content).map_err(|e| e.with_path(file))?;
+    for update in updates {
+        match merge_index(&rows, |r| &r.tag, &update) {
+            Ok(i) => {
+                rows[i].tag.value = update.value;
+                rows[i].fields = None;
+            }
+            Err(i) => rows.insert(
+                i,
+                TagRow {
+                    tag: update,
+                    fields: None,
+                },
+            ),
+        }
+    }
+    fs::write(file, csv::write_csv(header.as_deref(), &rows))
+        .map_err(|e| GfMetadataError::io(file, e))
+}
+
+pub fn read_tags(root: &Path) -> Result<Vec<Tag>, GfMetadataError> {
+    read_tags_with(root, read_csv_file)
+}
+
+/// [`read_tags`], reading each csv file with read_file
+pub(crate) fn read_tags_with(
+    root: &Path,
+    read_file: impl Fn(&Path) -> Result<Vec<Tag>, GfMetadataError>,
+) -> Result<Vec<Tag>, GfMetadataError> {
+    let mut tag_dir = root.to_path_buf();
+    tag_dir.push("tags/all");
+    if let Some(e) = missing_dir(&tag_dir) {
+        return Err(e);
+    }
+    let mut tags = Vec::new();
+    for entry in fs::read_dir(&tag_dir).map_err(|e| GfMetadataError::io(&tag_dir, e))? {
+        let entry = entry.map_err(|e| GfMetadataError::io(&tag_dir, e))?;
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("csv") {
+            continue;
+        }
+        tags.extend(read_file(&path)?);
+    }
+    Ok(tags)
+}
+
+pub fn read_tag_metadata(root: &Path) -> Result<Vec<TagMetadata>, GfMetadataError> {
+    read_tag_metadata_with(root, read_csv_file)
+}
+
+/// [`read_tag_metadata`], reading the csv file with read_file
+pub(crate) fn read_tag_metadata_with(
+    root: &Path,
+    read_file: impl Fn(&Path) -> Result<Vec<TagMetadata>, GfMetadataError>,
+) -> Result<Vec<TagMetadata>, GfMetadataError> {
+    if let Some(e) = missing_dir(&root.join("tags")) {
+        return Err(e);
+    }
+    read_file(&tag_metadata_file(root))
+}
+
+fn tag_metadata_file(root: &Path) -> PathBuf {
+    root.join("tags/tags_metadata.csv")
+}
+
+/// A position in design space, as written in tag files and the css2 API, e.g. ital,wght@1,100
+///
+/// The empty location means the tag applies to the family as a whole.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AxisLocation(Vec<(String, f32)>);
+
+impl AxisLocation {
+    pub fn new(positions: impl IntoIterator<Item = (String, f32)>) -> Self {
+        Self(positions.into_iter().collect())
+    }
+
+    /// True if no axis positions are specified
+    pub fn is_default(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// The position on the axis with this tag, if specified
+    pub fn get(&self, tag: &str) -> Option<f32> {
+        self.0.iter().find(|(t, _)| t == tag).map(|(_, v)| *v)
+    }
+
+    /// (axis tag, position) in the order written
+    pub fn iter(&self) -> impl Iterator<Item = (&str, f32)> {
+        self.0.iter().map(|(t, v)| (t.as_str(), *v))
+    }
+
+    /// Whether this location identifies the named instance described by font
+    ///
+    /// Only ital and wght, which map to style and weight, can be matched. A location that
+    /// specifies any other axis never matches.
+    pub fn matches_font(&self, font: &FontProto) -> bool {
+        !self.is_default()
+            && self.iter().all(|(tag, value)| match tag {
+                "ital" => (value != 0.0) == (font.style() == "italic"),
+                "wght" => value == font.weight() as f32,
+                _ => false,
+            })
+    }
+}
+
+impl FromStr for AxisLocation {
+    type Err = GfMetadataError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Ok(Default::default());
+        }
+        let Some((tags, values)) = s.split_once('@') else {
+            return Err(GfMetadataError::csv(format!(
+                "Invalid location {s:?}, expected tags@values"
+            )));
+        };
+        let tags = tags.split(',').map(str::trim).collect::<Vec<_>>();
+        let values = values.split(',').map(str::trim).collect::<Vec<_>>();
+        if tags.len() != values.len() || tags.iter().any(|t| t.is_empty() || t.len() > 4) {
+            return Err(GfMetadataError::csv(format!(
+                "Invalid location {s:?}, expected one value per axis tag"
+            )));
+        }
+        tags.into_iter()
+            .zip(values)
+            .map(|(t, v)| Ok((t.to_string(), parse_f32(v, "axis position")?)))
+            .collect::<Result<_, _>>()
+            .map(AxisLocation)
+    }
+}
+
+impl fmt::Display for AxisLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_default() {
+            return Ok(());
+        }
+        let tags = self.0.iter().map(|(t, _)| t.as_str()).collect::<Vec<_>>();
+        let values = self
+            .0
+            .iter()
+            .map(|(_, v)| v.to_string())
+            .collect::<Vec<_>>();
+        write!(f, "{}@{}", tags.join(","), values.join(","))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tag {
+    pub family: String,
+    pub loc: AxisLocation,
+    pub tag: String,
+    pub value: f32,
+}
+
+fn parse_f32(value: &str, what: &str) -> Result<f32, GfMetadataError> {
+    f32::from_str(value).map_err(|_| GfMetadataError::csv(format!("Invalid {what} {value:?}")))
+}
+
+impl CsvRecord for Tag {
+    fn from_record(fields: &[String]) -> Result<Self, GfMetadataError> {
+        let (family, loc, tag, value) = match fields {
+            [family, tag, value] => (family, "", tag, value),
+            [family, loc, tag, value] => (family, loc.as_str(), tag, value),
+            _ => {
+                return Err(GfMetadataError::csv(format!(
+                    "Unparseable tag, expected 3 or 4 values but got {}",
+                    fields.len()
+                )));
+            }
+        };
+        Ok(Tag {
+            family: family.to_string(),
+            loc: AxisLocation::from_str(loc)?,
+            tag: tag.to_string(),
+            value: parse_f32(value, "tag value")?,
+        })
+    }
+
+    fn to_record(&self) -> Vec<String> {
+        vec![
+            self.family.clone(),
+            self.loc.to_string(),
+            self.tag.clone(),
+            self.value.to_string(),
+        ]
+    }
+
+    fn is_header(fields: &[String]) -> bool {
+        matches!(fields.len(), 3 | 4) && f32::from_str(&fields[fields.len() - 1]).is_err()
+    }
+}
+
+impl FromStr for Tag {
+    type Err = GfMetadataError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Tag::from_record(&csv::parse_record(s)?)
+    }
+}
+
+impl Tag {
+    /// This tag as a line of a tags csv file, without a line ending
+    ///
+    /// Reads back unchanged with [`Tag::from_str`].
+    pub fn to_csv(&self) -> String {
+        csv::write_record(&self.to_record())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TagMetadata {
+    pub tag: String,
+    pub min_value: f32,
+    pub max_value: f32,
+    pub prompt_name: String,
+}
+
+impl CsvRecord for TagMetadata {
+    fn from_record(fields: &[String]) -> Result<Self, GfMetadataError> {
+        let [tag, min, max, prompt_name] = fields else {
+            return Err(GfMetadataError::csv(format!(
+                "Unparseable tag metadata, expected 4 values but got {}",
+                fields.len()
+            )));
+        };
+        Ok(TagMetadata {
+            tag: tag.clone(),
+            min_value: parse_f32(min, "min value")?,
+            max_value: parse_f32(max, "max value")?,
+            prompt_name: prompt_name.clone(),
+        })
+    }
+
+    fn to_record(&self) -> Vec<String> {
+        vec![
+            self.tag.clone(),
+            self.min_value.to_string(),
+            self.max_value.to_string(),
+            self.prompt_name.clone(),
+        ]
+    }
+
+    fn is_header(fields: &[String]) -> bool {
+        fields.len() == 4
+            && f32::from_str(&fields[1]).is_err()
+            && f32::from_str(&fields[2]).is_err()
+    }
+}
+
+impl FromStr for TagMetadata {
+    type Err = GfMetadataError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        TagMetadata::from_record(&csv::parse_record(s)?)
+    }
+}
+
+impl TagMetadata {
+    /// This metadata as a line of tags_metadata.csv, without a line ending
+    ///
+    /// Reads back unchanged with [`TagMetadata::from_str`].
+    pub fn to_csv(&self) -> String {
+        csv::write_record(&self.to_record())
+    }
+}
+
+/// Answers questions about tag values, normalized to 0..1 using [`TagMetadata`] where available.
+pub struct TagIndex {
+    tags: Vec<Tag>,
+    by_family: HashMap<String, Vec<usize>>,
+    by_tag: HashMap<String, Vec<usize>>,
+    ranges: HashMap<String, (f32, f32)>,
+}
+
+impl TagIndex {
+    pub fn new(tags: &[Tag], metadata: &[TagMetadata]) -> Self {
+        let mut by_family: HashMap<_, Vec<_>> = HashMap::new();
+        let mut by_tag: HashMap<_, Vec<_>> = HashMap::new();
+        for (i, tag) in tags.iter().enumerate() {
+            by_family.entry(tag.family.clone()).or_default().push(i);
+            by_tag.entry(tag.tag.clone()).or_default().push(i);
+        }
+        Self {
+            tags: tags.to_vec(),
+            by_family,
+            by_tag,
+            ranges: metadata
+                .iter()
+                .map(|m| (m.tag.clone(), (m.min_value, m.max_value)))
+                .collect(),
+        }
+    }
+
+    /// Scale value to 0..1 per the min and max for tag from the tag metadata
+    ///
+    /// None if tag has no metadata or an empty range.
+    pub fn normalize(&self, tag: &str, value: f32) -> Option<f32> {
+        let (min, max) = self.ranges.get(tag).copied()?;
+        (max > min).then(|| ((value - min) / (max - min)).clamp(0.0, 1.0))
+    }
+
+    /// Every tag for a family, by family name
+    pub fn tags_for_family<'a>(&'a self, family: &str) -> impl Iterator<Item = &'a Tag> + use<'a> {
+        self.by_family
+            .get(family)
+            .into_iter()
+            .flatten()
+            .map(|i| &self.tags[*i])
+    }
+
+    /// The raw value of tag for family at loc
+    pub fn value(&self, family: &str, tag: &str, loc: &AxisLocation) -> Option<f32> {
+        self.tags_for_family(family)
+            .find(|t| t.tag == tag && t.loc == *loc)
+            .map(|t| t.value)
+    }
+
+    /// The normalized value of tag for family at loc
+    pub fn normalized_value(&self, family: &str, tag: &str, loc: &AxisLocation) -> Option<f32> {
+        self.value(family, tag, loc)
+            .and_then(|v| self.normalize(tag, v))
+    }
+
+    /// Every (tag entry, normalized value) for tag with a normalized value above threshold
+    ///
+    /// Sorted by descending value.
+    pub fn above(&self, tag: &str, threshold: f32) -> Vec<(&Tag, f32)> {
+        let mut matches = self
+            .by_tag
+            .get(tag)
+            .into_iter()
+            .flatten()
+            .map(|i| &self.tags[*i])
+            .filter_map(|t| self.normalize(tag, t.value).map(|v| (t, v)))
+            .filter(|(_, v)| *v > threshold)
+            .collect::<Vec<_>>();
+        matches.sort_by(|(a, va), (b, vb)| vb.total_cmp(va).then_with(|| a.family.cmp(&b.family)));
+        matches
+    }
+
+    /// The tags that apply to font, one entry per tag
+    ///
+    /// A tag recorded at a location that matches the font's style and weight takes precedence
+    /// over one recorded for the family as a whole.
+    pub fn tags_for_font<'a>(&'a self, family: &FamilyProto, font: &FontProto) -> Vec<&'a Tag> {
+        let mut tags: Vec<&Tag> = Vec::new();
+        for tag in self.tags_for_family(family.name()) {
+            if !tag.loc.is_default() && !tag.loc.matches_font(font) {
+                continue;
+            }
+            match tags.iter_mut().find(|t| t.tag == tag.tag) {
+                Some(existing) if existing.loc.is_default() => *existing = tag,
+                Some(_) => (),
+                None => tags.push(tag),
+            }
+        }
+        tags
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{read_family, test_util::testdata_file_content};
+
+    #[test]
+    fn tag_error_has_no_location() {
+        let Err(GfMetadataError::Csv { path, line, .. }) = Tag::from_str("Roboto, 1") else {
+            panic!("Should fail to parse");
+        };
+        assert_eq!((None, None), (path, line));
+    }
+
+    #[test]
+    fn tag_value_error() {
+        assert!(matches!(
+            Tag::from_str("Roboto, /quant/stroke_width_min, wide"),
+            Err(GfMetadataError::Csv { .. })
+        ));
+    }
+
+    #[test]
+    fn parse_tag_unterminated_quote() {
+        assert!(
+            Tag::from_str("Georama, \"ital,wght@1,100, /quant/stroke_width_min, 16.97").is_err()
+        );
+    }
+
+    #[test]
+    fn parse_tags_with_header() {
+        let tags: Vec<Tag> = csv::parse_csv(
+            "Family,Axes,Group/Tag,Weight\nRoboto, /quant/stroke_width_min, 26.31\n",
+        )
+        .unwrap();
+        assert_eq!(1, tags.len());
+        let metadata: Vec<TagMetadata> =
+            csv::parse_csv("/quant/stroke_width_min, 0, 100, thin\n").unwrap();
+        assert_eq!(1, metadata.len());
+    }
+
+    #[test]
+    fn tag_errors_have_line_numbers() {
+        let Err(GfMetadataError::Csv { line, .. }) =
+            csv::parse_csv::<Tag>("Roboto, /quant/stroke_width_min, 26.31\n\nRoboto, 1\n")
+        else {
+            panic!("Should fail");
+        };
+        assert_eq!(Some(3), line);
+    }
+
+    #[test]
+    fn parse_tag3() {
+        Tag::from_str("Roboto Slab, /quant/stroke_width_min, 26.31").expect("To parse");
+    }
+
+    #[test]
+    fn parse_tag4() {
+        Tag::from_str("Roboto Slab, wght@100, /quant/stroke_width_min, 26.31").expect("To parse");
+    }
+
+    #[test]
+    fn parse_tag_quoted() {
+        let tag = Tag::from_str("Georama, \"ital,wght@1,100\", /quant/stroke_width_min, 16.97")
+            .expect("To parse");
+        assert_eq!("ital,wght@1,100", tag.loc.to_string());
+    }
+
+    #[test]
+    fn parse_tag_quoted2() {
+        let tag = Tag::from_str("\"\",t,1").expect("To parse");
+        assert_eq!("", tag.family);
+    }
+
+    #[test]
+    fn parse_tag_loc() {
+        let tag = Tag::from_str("Georama, \"ital,wght@1,100\", /quant/stroke_width_min, 16.97")
+            .expect("To parse");
+        assert_eq!(
+            vec![("ital", 1.0), ("wght", 100.0)],
+            tag.loc.iter().collect::<Vec<_>>()
+        );
+        assert_eq!("ital,wght@1,100", tag.loc.to_string());
+    }
+
+    #[test]
+    fn parse_bad_tag_loc() {
+        assert!(AxisLocation::from_str("wght").is_err());
+        assert!(AxisLocation::from_str("ital,wght@1").is_err());
+        assert!(AxisLocation::from_str("wght@heavy").is_err());
+    }
+
+    fn tag_index() -> TagIndex {
+        let tags: Vec<Tag> = csv::parse_csv(
+            r#"
+            Roboto, /Expressive/Calm, 50
+            Roboto, wght@100, /Expressive/Calm, 20
+            Roboto, "ital,wght@1,400", /Expressive/Calm, 90
+            Roboto, /quant/stroke_width_min, 26.31
+            Kosugi Maru, /Expressive/Calm, 75
+            "#,
+        )
+        .unwrap();
+        let metadata: Vec<TagMetadata> = csv::parse_csv("/Expressive/Calm, 0, 100, calm").unwrap();
+        TagIndex::new(&tags, &metadata)
+    }
+
+    #[test]
+    fn tag_index_values() {
+        let index = tag_index();
+        let loc = AxisLocation::from_str("wght@100").unwrap();
+        assert_eq!(Some(20.0), index.value("Roboto", "/Expressive/Calm", &loc));
+        assert_eq!(
+            Some(0.5),
+            index.normalized_value("Roboto", "/Expressive/Calm", &AxisLocation::default())
+        );
+        assert_eq!(None, index.normalize("/quant/stroke_width_min", 26.31));
+    }
+
+    #[test]
+    fn tag_index_above() {
+        let index = tag_index();
+        assert_eq!(
+            vec![("Roboto", 0.9), ("Kosugi Maru", 0.75)],
+            index
+                .above("/Expressive/Calm", 0.6)
+                .into_iter()
+                .map(|(t, v)| (t.family.as_str(), v))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn tag_index_for_font() {
+        let index = tag_index();
+        let roboto = read_family(&testdata_file_content("roboto-metadata.pb")).unwrap();
+        let values = |font: &FontProto| {
+            index
+                .tags_for_font(&roboto, font)
+                .into_iter()
+                .map(|t| (t.tag.as_str(), t.value))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            vec![
+                ("/Expressive/Calm", 50.0),
+                ("/quant/stroke_width_min", 26.31)
+            ],
+            values(&roboto.fonts[0])
+        );
+        assert_eq!(
+            vec![
+                ("/Expressive/Calm", 90.0),
+                ("/quant/stroke_width_min", 26.31)
+            ],
+            values(&roboto.fonts[1])
+        );
+    }
+
+    #[test]
+    fn tags_round_trip() {
+        let csv = "Roboto,,/Expressive/Calm,50\nGeorama,\"ital,wght@1,100\",/quant/stroke_width_min,16.97\n";
+        let tags: Vec<Tag> = csv::parse_csv(csv).unwrap();
+        assert_eq!(csv, write_tags(&tags));
+        for tag in tags {
+            assert_eq!(tag, Tag::from_str(&tag.to_csv()).unwrap());
+        }
+    }
+
+    #[test]
+    fn tag_metadata_round_trip() {
+        let csv = "/Expressive/Calm,0,100,calm\n/quant/stroke_width_min,2.5,40,\"thin, or not\"\n";
+        let metadata: Vec<TagMetadata> = csv::parse_csv(csv).unwrap();
+        assert_eq!(csv, write_tag_metadata(&metadata));
+    }
+
+    #[test]
+    fn merge_tags_preserves_other_rows() {
+        let mut tags: Vec<Tag> =
+            csv::parse_csv("A,,/x,1\nB,,/x,2\nB,wght@400,/x,3\nC,,/x,4\n").unwrap();
+        merge_tags(
+            &mut tags,
+            [
+                Tag::from_str("B,wght@400,/x,30").unwrap(),
+                Tag::from_str("B,,/y,5").unwrap(),
+                Tag::from_str("D,,/x,6").unwrap(),
+            ],
+        );
+        assert_eq!(
+            "A,,/x,1\nB,,/x,2\nB,wght@400,/x,30\nB,,/y,5\nC,,/x,4\nD,,/x,6\n",
+            write_tags(&tags)
+        );
+    }
+
+    #[test]
+    fn merge_tags_file_keeps_header() {
+        let file = std::env::temp_dir().join(format!("merge_tags_{}.csv", std::process::id()));
+        fs::write(&file, "Family,Axes,Group/Tag,Weight\nA,,/x,1\n").unwrap();
+        merge_tags_file(&file, [Tag::from_str("A,,/x,2").unwrap()]).unwrap();
+        let content = fs::read_to_string(&file).unwrap();
+        fs::remove_file(&file).unwrap();
+        assert_eq!("Family,Axes,Group/Tag,Weight\nA,,/x,2\n", content);
+    }
+
+    #[test]
+    fn merge_tags_file_keeps_values_as_written() {
+        let file =
+            std::env::temp_dir().join(format!("merge_tags_as_written_{}.csv", std::process::id()));
+        fs::write(&file, "A,,/x,8.0\nA,,/y,0.50\nB,,/x,1.0\n").unwrap();
+        merge_tags_file(
+            &file,
+            [
+                Tag::from_str("A,,/y,0.750").unwrap(),
+                Tag::from_str("A,,/z,2.0").unwrap(),
+            ],
+        )
+        .unwrap();
+        let content = fs::read_to_string(&file).unwrap();
+        fs::remove_file(&file).unwrap();
+        assert_eq!("A,,/x,8.0\nA,,/y,0.75\nA,,/z,2\nB,,/x,1.0\n", content);
+    }
+}
@@ -0,0 +1,39 @@
+//! Fixtures shared by the tests of every module
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+pub(crate) fn testdata_dir() -> PathBuf {
+    // cargo test seems to run in the project directory
+    // VSCode test seems to run in the workspace directory
+    // probe for the file we want in hopes of finding it regardless
+
+    ["./resources/testdata", "../resources/testdata"]
+        .iter()
+        .map(PathBuf::from)
+        .find(|pb| pb.exists())
+        .unwrap()
+}
+
+pub(crate) fn testdata_file_content(relative_path: &str) -> String {
+    let mut p = testdata_dir();
+    p.push(relative_path);
+    fs::read_to_string(p).unwrap()
+}
+
+/// A fresh, empty, directory under the system temp dir
+pub(crate) fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("{name}_{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Creates the directories that make root look like a google/fonts checkout
+pub(crate) fn fake_repo(root: &Path) {
+    for marker in ["ofl", "apache", "tags", "lang"] {
+        fs::create_dir_all(root.join(marker)).unwrap();
+    }
+}
@@ -0,0 +1,338 @@
+//! Writes protobuf text format the way google/fonts does.
+//!
+//! google/fonts writes METADATA.pb with Python's `text_format.MessageToString(m, as_utf8=True)`.
+//! That differs from [`protobuf::text_format::print_to_string_pretty`]: fields come out in
+//! field number order, floats always have a fractional part or exponent, strings keep UTF-8,
+//! and map entries are sorted by key.
+
+use std::fmt::Write;
+
+use protobuf::{
+    MessageDyn,
+    reflect::{MessageRef, ReflectFieldRef, ReflectValueRef},
+};
+
+/// Write message as text format, each field on its own line, two space indent per level
+pub(crate) fn write_message(message: &dyn MessageDyn) -> String {
+    let mut out = String::new();
+    write_fields(&MessageRef::from(message), &mut out, 0);
+    out
+}
+
+/// The path to the first required field message leaves unset, e.g. `fonts[1].filename`
+pub(crate) fn missing_required_field(message: &dyn MessageDyn) -> Option<String> {
+    let message = MessageRef::from(message);
+    let descriptor = message.descriptor_dyn();
+    let mut fields = descriptor.fields().collect::<Vec<_>>();
+    fields.sort_by_key(|f| f.number());
+
+    for field in fields {
+        match field.get_reflect(&*message) {
+            ReflectFieldRef::Optional(value) => match value.value() {
+                Some(ReflectValueRef::Message(m)) => {
+                    if let Some(missing) = missing_required_field(&*m) {
+                        return Some(format!("{}.{missing}", field.name()));
+                    }
+                }
+                Some(_) => (),
+                None if field.is_required() => return Some(field.name().to_string()),
+                None => (),
+            },
+            ReflectFieldRef::Repeated(values) => {
+                for (i, value) in values.into_iter().enumerate() {
+                    if let ReflectValueRef::Message(m) = value
+                        && let Some(missing) = missing_required_field(&*m)
+                    {
+                        return Some(format!("{}[{i}].{missing}", field.name()));
+                    }
+                }
+            }
+            ReflectFieldRef::Map(map) => {
+                for (key, value) in map.into_iter() {
+                    if let ReflectValueRef::Message(m) = value
+                        && let Some(missing) = missing_required_field(&*m)
+                    {
+                        return Some(format!("{}[{key}].{missing}", field.name()));
+                    }
+                }
+            }
+        }
+    }
+    None
+}
+
+fn write_fields(message: &MessageRef, out: &mut String, indent: usize) {
+    let descriptor = message.descriptor_dyn();
+    let mut fields = descriptor.fields().collect::<Vec<_>>();
+    fields.sort_by_key(|f| f.number());
+
+    for field in fields {
+        match field.get_reflect(&**message) {
+            ReflectFieldRef::Optional(value) => {
+                if let Some(value) = value.value() {
+                    write_field(out, indent, field.name(), value);
+                }
+            }
+            ReflectFieldRef::Repeated(values) => {
+                for value in values {
+                    write_field(out, indent, field.name(), value);
+                }
+            }
+            ReflectFieldRef::Map(map) => {
+                let mut entries = map.into_iter().collect::<Vec<_>>();
+                entries.sort_by(|(k1, _), (k2, _)| map_key_order(k1, k2));
+                for (key, value) in entries {
+                    write_indent(out, indent);
+                    out.push_str(field.name());
+                    out.push_str(" {\n");
+                    write_field(out, indent + 1, "key", key);
+                    write_field(out, indent + 1, "value", value);
+                    write_indent(out, indent);
+                    out.push_str("}\n");
+                }
+            }
+        }
+    }
+}
+
+fn map_key_order(k1: &ReflectValueRef, k2: &ReflectValueRef) -> std::cmp::Ordering {
+    match (k1, k2) {
+        (ReflectValueRef::String(a), ReflectValueRef::String(b)) => a.cmp(b),
+        (ReflectValueRef::I32(a), ReflectValueRef::I32(b)) => a.cmp(b),
+        (ReflectValueRef::I64(a), ReflectValueRef::I64(b)) => a.cmp(b),
+        (ReflectValueRef::U32(a), ReflectValueRef::U32(b)) => a.cmp(b),
+        (ReflectValueRef::U64(a), ReflectValueRef::U64(b)) => a.cmp(b),
+        (ReflectValueRef::Bool(a), ReflectValueRef::Bool(b)) => a.cmp(b),
+        _ => std::cmp::Ordering::Equal,
+    }
+}
+
+fn write_indent(out: &mut String, indent: usize) {
+    for _ in 0..indent {
+        out.push_str("  ");
+    }
+}
+
+fn write_field(out: &mut String, indent: usize, name: &str, value: ReflectValueRef) {
+    write_indent(out, indent);
+    out.push_str(name);
+    match value {
+        ReflectValueRef::Message(m) => {
+            out.push_str(" {\n");
+            write_fields(&m, out, indent + 1);
+            write_indent(out, indent);
+            out.push('}');
+        }
+        ReflectValueRef::Enum(descriptor, number) => {
+            match descriptor.value_by_number(number) {
+                Some(v) => write!(out, ": {}", v.name()),
+                None => write!(out, ": {number}"),
+            }
+            .unwrap();
+        }
+        ReflectValueRef::String(s) => {
+            out.push_str(": ");
+            write_string(out, s);
+        }
+        ReflectValueRef::Bytes(b) => {
+            out.push_str(": ");
+            write_bytes(out, b);
+        }
+        ReflectValueRef::I32(v) => write!(out, ": {v}").unwrap(),
+        ReflectValueRef::I64(v) => write!(out, ": {v}").unwrap(),
+        ReflectValueRef::U32(v) => write!(out, ": {v}").unwrap(),
+        ReflectValueRef::U64(v) => write!(out, ": {v}").unwrap(),
+        ReflectValueRef::Bool(v) => write!(out, ": {v}").unwrap(),
+        ReflectValueRef::F32(v) => write!(out, ": {}", format_float(format!("{v:e}"))).unwrap(),
+        ReflectValueRef::F64(v) => write!(out, ": {}", format_float(format!("{v:e}"))).unwrap(),
+    }
+    out.push('\n');
+}
+
+/// Quote and escape a string as Python's CEscape does with as_utf8, keeping non-ASCII as is
+fn write_string(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            '"' => out.push_str("\\\""),
+            '\'' => out.push_str("\\'"),
+            '\\' => out.push_str("\\\\"),
+            c if c.is_ascii_control() => write!(out, "\\{:03o}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+fn write_bytes(out: &mut String, bytes: &[u8]) {
+    out.push('"');
+    for b in bytes {
+        match b {
+            b'\n' => out.push_str("\\n"),
+            b'\r' => out.push_str("\\r"),
+            b'\t' => out.push_str("\\t"),
+            b'"' => out.push_str("\\\""),
+            b'\'' => out.push_str("\\'"),
+            b'\\' => out.push_str("\\\\"),
+            0x20..0x7f => out.push(*b as char),
+            b => write!(out, "\\{b:03o}").unwrap(),
+        }
+    }
+    out.push('"');
+}
+
+/// Reformat the shortest round trip representation of a float, as produced by `{:e}`,
+/// the way Python's repr would: 75.0, 0.5, 1e-05, 1.5e+16, inf, nan.
+fn format_float(exponential: String) -> String {
+    let Some((mantissa, exponent)) = exponential.split_once('e') else {
+        // inf, -inf or NaN
+        return exponential.to_lowercase();
+    };
+    let exponent: i32 = exponent.parse().expect("{:e} writes an integer exponent");
+    let (sign, mantissa) = match mantissa.strip_prefix('-') {
+        Some(m) => ("-", m),
+        None => ("", mantissa),
+    };
+    let digits = mantissa.replace('.', "");
+
+    if !(-4..16).contains(&exponent) {
+        let (first, rest) = digits.split_at(1);
+        let rest = if rest.is_empty() {
+            String::new()
+        } else {
+            format!(".{rest}")
+        };
+        let exponent_sign = if exponent < 0 { '-' } else { '+' };
+        return format!("{sign}{first}{rest}e{exponent_sign}{:02}", exponent.abs());
+    }
+
+    let point = exponent + 1;
+    let (int, frac) = if point <= 0 {
+        (
+            "0".to_string(),
+            format!("{}{digits}", "0".repeat(-point as usize)),
+        )
+    } else if point as usize >= digits.len() {
+        (
+            format!("{digits}{}", "0".repeat(point as usize - digits.len())),
+            "0".to_string(),
+        )
+    } else {
+        let (int, frac) = digits.split_at(point as usize);
+        (int.to_string(), frac.to_string())
+    };
+    format!("{sign}{int}.{frac}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AxisSegmentProto, read_family, test_util::testdata_file_content, write_family};
+
+    #[test]
+    fn floats_match_python_repr() {
+        for (expected, value) in [
+            ("75.0", 75.0),
+            ("0.5", 0.5),
+            ("-12.25", -12.25),
+            ("0.0001", 0.0001),
+            ("1e-05", 0.00001),
+            ("1234567.0", 1234567.0),
+            ("1e+16", 1e16),
+            ("-1.5e+20", -1.5e20),
+            ("inf", f64::INFINITY),
+            ("nan", f64::NAN),
+        ] {
+            assert_eq!(expected, format_float(format!("{value:e}")), "{value}");
+        }
+    }
+
+    #[test]
+    fn f32_uses_shortest_f32_digits() {
+        assert_eq!("0.1", format_float(format!("{:e}", 0.1f32)));
+        assert_eq!("16.97", format_float(format!("{:e}", 16.97f32)));
+    }
+
+    #[test]
+    fn strings_are_escaped() {
+        let mut out = String::new();
+        write_string(&mut out, "Ünïcode \"quoted\" it's\na\\b\u{1}");
+        assert_eq!(r#""Ünïcode \"quoted\" it\'s\na\\b\001""#, out);
+    }
+
+    fn assert_round_trips(metadata: &str) {
+        let family = read_family(metadata).unwrap();
+        assert_eq!(metadata, write_family(&family));
+    }
+
+    #[test]
+    fn write_roboto_metadata() {
+        assert_round_trips(&testdata_file_content("roboto-metadata.pb"));
+    }
+
+    #[test]
+    fn write_kosugimaru_metadata() {
+        // The test copy lost the trailing newline every METADATA.pb in google/fonts has
+        assert_round_trips(&(testdata_file_content("kosugimaru-metadata.pb") + "\n"));
+    }
+
+    #[test]
+    fn write_wix_metadata() {
+        // Has the undocumented position field
+        assert_round_trips(&testdata_file_content("wixmadefortext-metadata.pb"));
+    }
+
+    #[test]
+    fn write_edited_metadata() {
+        let mut family = read_family(&testdata_file_content("kosugimaru-metadata.pb")).unwrap();
+        // Set out of field number order to show it doesn't matter
+        family.set_display_name("Kosugi \"Maru\"".to_string());
+        family.set_is_noto(false);
+        family
+            .sample_glyphs
+            .insert("b".to_string(), "B".to_string());
+        family
+            .sample_glyphs
+            .insert("a".to_string(), "A".to_string());
+        family.axes.push(AxisSegmentProto {
+            tag: Some("wght".to_string()),
+            min_value: Some(100.0),
+            max_value: Some(900.5),
+            ..Default::default()
+        });
+
+        let written = write_family(&family);
+        assert!(
+            written.contains(concat!(
+                "subsets: \"menu\"\n",
+                "axes {\n",
+                "  tag: \"wght\"\n",
+                "  min_value: 100.0\n",
+                "  max_value: 900.5\n",
+                "}\n",
+                "source {\n",
+            )),
+            "{written}"
+        );
+        assert!(
+            written.contains(concat!(
+                "is_noto: false\n",
+                "sample_glyphs {\n",
+                "  key: \"a\"\n",
+                "  value: \"A\"\n",
+                "}\n",
+                "sample_glyphs {\n",
+                "  key: \"b\"\n",
+                "  value: \"B\"\n",
+                "}\n",
+                "display_name: \"Kosugi \\\"Maru\\\"\"\n",
+                "primary_script: \"Jpan\"\n",
+            )),
+            "{written}"
+        );
+        assert_round_trips(&written);
+    }
+}
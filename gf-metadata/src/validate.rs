@@ -0,0 +1,804 @@
+//! Rule based checks of METADATA.pb against the font binaries it describes.
+
+use std::{
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+use skrifa::{
+    FontRef, MetadataProvider, Tag,
+    raw::{TableProvider, tables::os2::SelectionFlags},
+    string::StringId,
+};
+
+use crate::{FamilyProto, FontProto, GoogleFonts};
+
+/// How much a finding matters
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// Probably wrong, worth a look
+    Warning,
+    /// Definitely wrong
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// A single check we make of a family
+#[derive(Debug, PartialEq, Eq)]
+pub struct Rule {
+    /// Stable identifier, e.g. for filtering findings
+    pub id: &'static str,
+    pub severity: Severity,
+    pub description: &'static str,
+}
+
+pub static METADATA_PARSES: Rule = Rule {
+    id: "metadata-parses",
+    severity: Severity::Error,
+    description: "METADATA.pb can be read and parsed",
+};
+
+pub static FONT_FILE_EXISTS: Rule = Rule {
+    id: "font-file-exists",
+    severity: Severity::Error,
+    description: "Every fonts.filename exists alongside METADATA.pb",
+};
+
+pub static FONT_PARSES: Rule = Rule {
+    id: "font-parses",
+    severity: Severity::Error,
+    description: "Every font binary can be parsed",
+};
+
+pub static WEIGHT_MATCHES: Rule = Rule {
+    id: "weight-matches",
+    severity: Severity::Error,
+    description: "fonts.weight is the OS/2 usWeightClass, or within the wght axis of a variable font",
+};
+
+pub static STYLE_MATCHES: Rule = Rule {
+    id: "style-matches",
+    severity: Severity::Error,
+    description: "fonts.style is italic if and only if the OS/2 fsSelection italic bit is set",
+};
+
+pub static POST_SCRIPT_NAME_MATCHES: Rule = Rule {
+    id: "post-script-name-matches",
+    severity: Severity::Error,
+    description: "fonts.post_script_name is the name table PostScript name (ID 6)",
+};
+
+pub static FULL_NAME_MATCHES: Rule = Rule {
+    id: "full-name-matches",
+    severity: Severity::Error,
+    description: "fonts.full_name is the name table full name (ID 4)",
+};
+
+pub static AXES_MATCH_FVAR: Rule = Rule {
+    id: "axes-match-fvar",
+    severity: Severity::Error,
+    description: "axes lists exactly the fvar axes of each variable font, with the same ranges",
+};
+
+pub static SUBSETS_COVERED: Rule = Rule {
+    id: "subsets-covered",
+    severity: Severity::Warning,
+    description: "The cmap of every font has sample characters for each subset",
+};
+
+pub static PRIMARY_LANGUAGE_RESOLVES: Rule = Rule {
+    id: "primary-language-resolves",
+    severity: Severity::Error,
+    description: "primary_language is a gflanguages language id",
+};
+
+pub static PRIMARY_SCRIPT_RESOLVES: Rule = Rule {
+    id: "primary-script-resolves",
+    severity: Severity::Error,
+    description: "primary_script is a gflanguages script id",
+};
+
+/// Every rule we check
+pub static RULES: &[&Rule] = &[
+    &METADATA_PARSES,
+    &FONT_FILE_EXISTS,
+    &FONT_PARSES,
+    &WEIGHT_MATCHES,
+    &STYLE_MATCHES,
+    &POST_SCRIPT_NAME_MATCHES,
+    &FULL_NAME_MATCHES,
+    &AXES_MATCH_FVAR,
+    &SUBSETS_COVERED,
+    &PRIMARY_LANGUAGE_RESOLVES,
+    &PRIMARY_SCRIPT_RESOLVES,
+];
+
+/// A handful of characters any font claiming a subset should have
+///
+/// The full subset definitions live in gftools, not google/fonts, so this is a spot check.
+/// Subsets not listed, such as menu, aren't checked.
+const SUBSET_SAMPLES: &[(&str, &str)] = &[
+    (
+        "latin",
+        "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789",
+    ),
+    ("latin-ext", "ĀāĂăĄąĆćČčĎďĘęĚěĞğŁłŃńŇňŐőŘřŚśŠšŤťŮůŰűŹźŻżŽž"),
+    (
+        "cyrillic",
+        "АБВГДЕЖЗИЙКЛМНОПРСТУФХЦЧШЩЪЫЬЭЮЯабвгдежзийклмнопрстуфхцчшщъыьэюя",
+    ),
+    ("cyrillic-ext", "ҐґҒғҚқҢңҮүҰұҲҳӘәӨө"),
+    ("greek", "ΑΒΓΔΕΖΗΘΙΚΛΜΝΞΟΠΡΣΤΥΦΧΨΩαβγδεζηθικλμνξοπρστυφχψω"),
+    ("greek-ext", "ἀἁἂἃἄἅἆἇἐἑἒἓἔἕ"),
+    ("vietnamese", "ĂăÂâĐđÊêÔôƠơƯưẠạẢảẤấẦầẨẩẪẫẬậ"),
+    ("japanese", "あいうえおかきくけこアイウエオカキクケコ日本語"),
+    ("korean", "가나다라마바사아자차카타파하"),
+    ("chinese-simplified", "的一是不了人我在有他这中大来上个国"),
+    ("chinese-traditional", "的一是不了人我在有他這中大來上個國"),
+    ("arabic", "ابتثجحخدذرزسشصضطظعغفقكلمنهوي"),
+    ("hebrew", "אבגדהוזחטיכלמנסעפצקרשת"),
+    ("devanagari", "अआइईउऊएऐओऔकखगघङचछजझञटठडढणतथदधनपफबभमयरलवशषसह"),
+    ("bengali", "অআইঈউঊএঐওঔকখগঘঙচছজঝঞ"),
+    ("tamil", "அஆஇஈஉஊஎஏஐஒஓஔகஙசஞடணதநபமயரலவழளறன"),
+    ("thai", "กขฃคฅฆงจฉชซฌญฎฏฐฑฒณดตถทธนบปผฝพฟภมยรลวศษสหฬอฮ"),
+    ("khmer", "កខគឃងចឆជឈញ"),
+];
+
+/// Something wrong with a family, or one of its fonts
+#[derive(Debug, PartialEq)]
+pub struct Finding {
+    pub rule: &'static Rule,
+    /// The filename of the font the finding is about, None if it's about the family
+    pub font: Option<String>,
+    pub message: String,
+}
+
+impl Finding {
+    fn family(rule: &'static Rule, message: String) -> Self {
+        Finding {
+            rule,
+            font: None,
+            message,
+        }
+    }
+
+    fn font(rule: &'static Rule, font: &FontProto, message: String) -> Self {
+        Finding {
+            rule,
+            font: Some(font.filename().to_string()),
+            message,
+        }
+    }
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} [{}] ", self.rule.severity, self.rule.id)?;
+        if let Some(font) = &self.font {
+            write!(f, "{font}: ")?;
+        }
+        write!(f, "{}", self.message)
+    }
+}
+
+/// The findings for a single METADATA.pb
+#[derive(Debug)]
+pub struct FamilyReport {
+    pub metadata_file: PathBuf,
+    /// The family name, None if METADATA.pb couldn't be parsed
+    pub family: Option<String>,
+    pub findings: Vec<Finding>,
+}
+
+/// The findings for every family in a repository
+#[derive(Debug, Default)]
+pub struct ValidationReport {
+    pub families: Vec<FamilyReport>,
+}
+
+impl ValidationReport {
+    /// How many findings have severity
+    pub fn count(&self, severity: Severity) -> usize {
+        self.findings()
+            .filter(|(_, f)| f.rule.severity == severity)
+            .count()
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.count(Severity::Error) > 0
+    }
+
+    /// Every finding along with the family it's for
+    pub fn findings(&self) -> impl Iterator<Item = (&FamilyReport, &Finding)> {
+        self.families
+            .iter()
+            .flat_map(|r| r.findings.iter().map(move |f| (r, f)))
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for report in self.families.iter().filter(|r| !r.findings.is_empty()) {
+            write!(f, "{}", report.metadata_file.display())?;
+            if let Some(family) = &report.family {
+                write!(f, " ({family})")?;
+            }
+            writeln!(f)?;
+            for finding in report.findings.iter() {
+                writeln!(f, "  {finding}")?;
+            }
+        }
+        write!(
+            f,
+            "{} errors, {} warnings in {} of {} families",
+            self.count(Severity::Error),
+            self.count(Severity::Warning),
+            self.families
+                .iter()
+                .filter(|r| !r.findings.is_empty())
+                .count(),
+            self.families.len()
+        )
+    }
+}
+
+fn english_name(font: &FontRef, id: StringId) -> Option<String> {
+    font.localized_strings(id)
+        .english_or_first()
+        .map(|s| s.to_string())
+}
+
+fn check_names(font: &FontProto, font_ref: &FontRef, findings: &mut Vec<Finding>) {
+    for (rule, id, expected) in [
+        (
+            &POST_SCRIPT_NAME_MATCHES,
+            StringId::POSTSCRIPT_NAME,
+            font.post_script_name(),
+        ),
+        (&FULL_NAME_MATCHES, StringId::FULL_NAME, font.full_name()),
+    ] {
+        match english_name(font_ref, id) {
+            Some(actual) if actual == expected => (),
+            Some(actual) => findings.push(Finding::font(
+                rule,
+                font,
+                format!("METADATA.pb has {expected:?}, name table has {actual:?}"),
+            )),
+            None => findings.push(Finding::font(
+                rule,
+                font,
+                format!("METADATA.pb has {expected:?}, name table has no name {id}"),
+            )),
+        }
+    }
+}
+
+fn check_weight_and_style(font: &FontProto, font_ref: &FontRef, findings: &mut Vec<Finding>) {
+    let Ok(os2) = font_ref.os2() else {
+        findings.push(Finding::font(
+            &FONT_PARSES,
+            font,
+            "No OS/2 table".to_string(),
+        ));
+        return;
+    };
+
+    let wght = font_ref.axes().get_by_tag(Tag::new(b"wght"));
+    let weight = font.weight() as f32;
+    match wght {
+        Some(wght) if weight < wght.min_value() || weight > wght.max_value() => {
+            findings.push(Finding::font(
+                &WEIGHT_MATCHES,
+                font,
+                format!(
+                    "weight {} is outside the wght axis, {}..{}",
+                    font.weight(),
+                    wght.min_value(),
+                    wght.max_value()
+                ),
+            ));
+        }
+        None if font.weight() != os2.us_weight_class() as i32 => {
+            findings.push(Finding::font(
+                &WEIGHT_MATCHES,
+                font,
+                format!(
+                    "weight {} but OS/2 usWeightClass is {}",
+                    font.weight(),
+                    os2.us_weight_class()
+                ),
+            ));
+        }
+        _ => (),
+    }
+
+    let is_italic = os2.fs_selection().contains(SelectionFlags::ITALIC);
+    if (font.style() == "italic") != is_italic {
+        findings.push(Finding::font(
+            &STYLE_MATCHES,
+            font,
+            format!(
+                "style {:?} but the OS/2 fsSelection italic bit is {}",
+                font.style(),
+                if is_italic { "set" } else { "clear" }
+            ),
+        ));
+    }
+}
+
+/// Ranges are floats in METADATA.pb and 16.16 fixed in fvar
+fn same_value(a: f32, b: f32) -> bool {
+    (a - b).abs() < 0.001
+}
+
+fn check_axes(
+    family: &FamilyProto,
+    font: &FontProto,
+    font_ref: &FontRef,
+    findings: &mut Vec<Finding>,
+) {
+    let fvar_axes = font_ref.axes();
+    for axis in family.axes.iter() {
+        let Some(fvar_axis) = fvar_axes.iter().find(|a| a.tag() == axis.tag()) else {
+            findings.push(Finding::font(
+                &AXES_MATCH_FVAR,
+                font,
+                format!("{} is in axes but not in fvar", axis.tag()),
+            ));
+            continue;
+        };
+        if !same_value(axis.min_value(), fvar_axis.min_value())
+            || !same_value(axis.max_value(), fvar_axis.max_value())
+        {
+            findings.push(Finding::font(
+                &AXES_MATCH_FVAR,
+                font,
+                format!(
+                    "{} is {}..{} in axes but {}..{} in fvar",
+                    axis.tag(),
+                    axis.min_value(),
+                    axis.max_value(),
+                    fvar_axis.min_value(),
+                    fvar_axis.max_value()
+                ),
+            ));
+        }
+    }
+    for fvar_axis in fvar_axes.iter() {
+        let tag = fvar_axis.tag().to_string();
+        if !family.axes.iter().any(|a| a.tag() == tag) {
+            findings.push(Finding::font(
+                &AXES_MATCH_FVAR,
+                font,
+                format!("{tag} is in fvar but not in axes"),
+            ));
+        }
+    }
+}
+
+fn check_subsets(
+    family: &FamilyProto,
+    font: &FontProto,
+    font_ref: &FontRef,
+    findings: &mut Vec<Finding>,
+) {
+    let charmap = font_ref.charmap();
+    for subset in family.subsets.iter() {
+        let Some((_, sample)) = SUBSET_SAMPLES.iter().find(|(s, _)| s == subset) else {
+            continue;
+        };
+        let missing: String = sample
+            .chars()
+            .filter(|c| charmap.map(*c).is_none())
+            .collect();
+        if !missing.is_empty() {
+            findings.push(Finding::font(
+                &SUBSETS_COVERED,
+                font,
+                format!("{subset} claimed but the cmap lacks {missing}"),
+            ));
+        }
+    }
+}
+
+/// Check font against its binary, returns whether it's variable or None if it can't be read
+fn check_font(
+    family: &FamilyProto,
+    font: &FontProto,
+    dir: &Path,
+    findings: &mut Vec<Finding>,
+) -> Option<bool> {
+    let font_file = dir.join(font.filename());
+    let data = match fs::read(&font_file) {
+        Ok(data) => data,
+        Err(e) => {
+            findings.push(Finding::font(
+                &FONT_FILE_EXISTS,
+                font,
+                format!("Unable to read {font_file:?}: {e}"),
+            ));
+            return None;
+        }
+    };
+    let font_ref = match FontRef::new(&data) {
+        Ok(font_ref) => font_ref,
+        Err(e) => {
+            findings.push(Finding::font(
+                &FONT_PARSES,
+                font,
+                format!("Unable to parse {font_file:?}: {e}"),
+            ));
+            return None;
+        }
+    };
+
+    let is_variable = !font_ref.axes().is_empty();
+    check_weight_and_style(font, &font_ref, findings);
+    check_names(font, &font_ref, findings);
+    if is_variable {
+        check_axes(family, font, &font_ref, findings);
+    }
+    check_subsets(family, font, &font_ref, findings);
+    Some(is_variable)
+}
+
+/// Check family, read from metadata_file, against its binaries and gflanguages
+pub(crate) fn validate_family(
+    gf: &GoogleFonts,
+    metadata_file: &Path,
+    family: &FamilyProto,
+) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    let dir = metadata_file.parent().unwrap_or(Path::new(""));
+    let is_variable = family
+        .fonts
+        .iter()
+        .map(|font| check_font(family, font, dir, &mut findings))
+        .collect::<Vec<_>>();
+
+    // Only meaningful if we could read every font
+    if !family.axes.is_empty() && is_variable.iter().all(|v| *v == Some(false)) {
+        findings.push(Finding::family(
+            &AXES_MATCH_FVAR,
+            "axes are declared but there are no variable fonts".to_string(),
+        ));
+    }
+    if family.has_primary_language() && gf.language(family.primary_language()).is_none() {
+        findings.push(Finding::family(
+            &PRIMARY_LANGUAGE_RESOLVES,
+            format!("No such language as {:?}", family.primary_language()),
+        ));
+    }
+    if family.has_primary_script() && gf.script(family.primary_script()).is_none() {
+        findings.push(Finding::family(
+            &PRIMARY_SCRIPT_RESOLVES,
+            format!("No such script as {:?}", family.primary_script()),
+        ));
+    }
+    findings
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashSet, path::PathBuf};
+
+    use write_fonts::{
+        FontBuilder,
+        tables::{
+            cmap::Cmap,
+            fvar::{AxisInstanceArrays, Fvar, VariationAxisRecord},
+            name::{Name, NameRecord},
+            os2::{self, Os2},
+        },
+        types::{Fixed, GlyphId, NameId, Tag as OtTag},
+    };
+
+    use super::*;
+    use crate::{
+        AxisSegmentProto, read_family,
+        test_util::{temp_dir, testdata_dir, testdata_file_content},
+    };
+
+    /// What to put in a font binary, by default exactly what [`test_family`] says
+    struct TestFont {
+        weight: u16,
+        italic: bool,
+        post_script_name: &'static str,
+        full_name: &'static str,
+        chars: &'static str,
+        wght: Option<(f64, f64)>,
+    }
+
+    impl Default for TestFont {
+        fn default() -> Self {
+            TestFont {
+                weight: 400,
+                italic: false,
+                post_script_name: "TestSans-Regular",
+                full_name: "Test Sans Regular",
+                chars: SUBSET_SAMPLES[0].1,
+                wght: None,
+            }
+        }
+    }
+
+    impl TestFont {
+        /// A font with no outlines, just the tables our rules look at
+        fn build(&self) -> Vec<u8> {
+            let mut os2 = Os2 {
+                us_weight_class: self.weight,
+                ..Default::default()
+            };
+            if self.italic {
+                os2.fs_selection = os2::SelectionFlags::ITALIC;
+            }
+            let name = Name::new(
+                [
+                    (NameId::FULL_NAME, self.full_name),
+                    (NameId::POSTSCRIPT_NAME, self.post_script_name),
+                ]
+                .into_iter()
+                .map(|(id, s)| NameRecord::new(3, 1, 0x409, id, s.to_string().into()))
+                .collect(),
+            );
+            let cmap = Cmap::from_mappings(
+                self.chars
+                    .chars()
+                    .enumerate()
+                    .map(|(i, c)| (c, GlyphId::new(i as u32 + 1))),
+            )
+            .unwrap();
+
+            let mut builder = FontBuilder::new();
+            builder
+                .add_table(&os2)
+                .unwrap()
+                .add_table(&name)
+                .unwrap()
+                .add_table(&cmap)
+                .unwrap();
+            if let Some((min, max)) = self.wght {
+                let axis = VariationAxisRecord::new(
+                    OtTag::new(b"wght"),
+                    Fixed::from_f64(min),
+                    Fixed::from_f64(400.0),
+                    Fixed::from_f64(max),
+                    0,
+                    NameId::new(256),
+                );
+                builder
+                    .add_table(&Fvar::new(AxisInstanceArrays::new(vec![axis], vec![])))
+                    .unwrap();
+            }
+            builder.build()
+        }
+    }
+
+    /// A family of one font, TestSans-Regular.ttf, variable on wght if wght is given
+    fn test_family(wght: Option<(f32, f32)>) -> FamilyProto {
+        let mut font = FontProto::new();
+        font.set_name("Test Sans".to_string());
+        font.set_style("normal".to_string());
+        font.set_weight(400);
+        font.set_filename("TestSans-Regular.ttf".to_string());
+        font.set_post_script_name("TestSans-Regular".to_string());
+        font.set_full_name("Test Sans Regular".to_string());
+
+        let mut family = FamilyProto::new();
+        family.set_name("Test Sans".to_string());
+        family.fonts.push(font);
+        family.subsets.push("latin".to_string());
+        if let Some((min, max)) = wght {
+            let mut axis = AxisSegmentProto::new();
+            axis.set_tag("wght".to_string());
+            axis.set_min_value(min);
+            axis.set_max_value(max);
+            family.axes.push(axis);
+        }
+        family
+    }
+
+    /// The rule id and severity of each finding for the only font of family, given its binary
+    fn font_findings(
+        name: &str,
+        family: &FamilyProto,
+        binary: &[u8],
+    ) -> Vec<(&'static str, Severity)> {
+        let dir = temp_dir(name);
+        fs::write(dir.join(family.fonts[0].filename()), binary).unwrap();
+        let mut findings = Vec::new();
+        check_font(family, &family.fonts[0], &dir, &mut findings);
+        fs::remove_dir_all(&dir).unwrap();
+        findings
+            .iter()
+            .map(|f| (f.rule.id, f.rule.severity))
+            .collect()
+    }
+
+    #[test]
+    fn binaries_matching_metadata() {
+        let family = test_family(None);
+        assert_eq!(
+            Vec::<(&str, Severity)>::new(),
+            font_findings("validate_static", &family, &TestFont::default().build())
+        );
+
+        let family = test_family(Some((100.0, 900.0)));
+        let font = TestFont {
+            wght: Some((100.0, 900.0)),
+            ..Default::default()
+        };
+        assert_eq!(
+            Vec::<(&str, Severity)>::new(),
+            font_findings("validate_variable", &family, &font.build())
+        );
+    }
+
+    #[test]
+    fn weight_mismatch() {
+        let font = TestFont {
+            weight: 700,
+            ..Default::default()
+        };
+        assert_eq!(
+            vec![("weight-matches", Severity::Error)],
+            font_findings("validate_weight", &test_family(None), &font.build())
+        );
+
+        // A variable font only needs weight to be within the wght axis
+        let mut family = test_family(Some((500.0, 900.0)));
+        let font = TestFont {
+            wght: Some((500.0, 900.0)),
+            ..Default::default()
+        };
+        assert_eq!(
+            vec![("weight-matches", Severity::Error)],
+            font_findings("validate_wght", &family, &font.build())
+        );
+        family.fonts[0].set_weight(500);
+        assert_eq!(
+            Vec::<(&str, Severity)>::new(),
+            font_findings("validate_wght", &family, &font.build())
+        );
+    }
+
+    #[test]
+    fn style_mismatch() {
+        let font = TestFont {
+            italic: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            vec![("style-matches", Severity::Error)],
+            font_findings("validate_style", &test_family(None), &font.build())
+        );
+    }
+
+    #[test]
+    fn name_mismatch() {
+        let font = TestFont {
+            post_script_name: "TestSans-Bold",
+            ..Default::default()
+        };
+        assert_eq!(
+            vec![("post-script-name-matches", Severity::Error)],
+            font_findings(
+                "validate_post_script_name",
+                &test_family(None),
+                &font.build()
+            )
+        );
+
+        let font = TestFont {
+            full_name: "Test Sans Bold",
+            ..Default::default()
+        };
+        assert_eq!(
+            vec![("full-name-matches", Severity::Error)],
+            font_findings("validate_full_name", &test_family(None), &font.build())
+        );
+    }
+
+    #[test]
+    fn axes_mismatch() {
+        let font = TestFont {
+            wght: Some((200.0, 900.0)),
+            ..Default::default()
+        };
+        assert_eq!(
+            vec![("axes-match-fvar", Severity::Error)],
+            font_findings(
+                "validate_axes",
+                &test_family(Some((100.0, 900.0))),
+                &font.build()
+            )
+        );
+
+        // Declaring no axes for a variable font is just as wrong
+        assert_eq!(
+            vec![("axes-match-fvar", Severity::Error)],
+            font_findings("validate_no_axes", &test_family(None), &font.build())
+        );
+    }
+
+    #[test]
+    fn subset_not_covered() {
+        let font = TestFont {
+            chars: "ABCDEFGHIJKLMNOPQRSTUVWXYZ",
+            ..Default::default()
+        };
+        assert_eq!(
+            vec![("subsets-covered", Severity::Warning)],
+            font_findings("validate_subsets", &test_family(None), &font.build())
+        );
+
+        // Subsets we have no sample for aren't checked
+        let mut family = test_family(None);
+        family.subsets = vec!["menu".to_string()];
+        assert_eq!(
+            Vec::<(&str, Severity)>::new(),
+            font_findings("validate_menu_subset", &family, &font.build())
+        );
+    }
+
+    #[test]
+    fn not_a_font() {
+        assert_eq!(
+            vec![("font-parses", Severity::Error)],
+            font_findings("validate_not_a_font", &test_family(None), b"not a font")
+        );
+    }
+
+    #[test]
+    fn validate_without_binaries() {
+        let gf = GoogleFonts::new(testdata_dir(), None);
+        let metadata_file = testdata_dir().join("kosugimaru-metadata.pb");
+        let kosugi = read_family(&testdata_file_content("kosugimaru-metadata.pb")).unwrap();
+        let findings = gf.validate_family(&metadata_file, &kosugi);
+        assert_eq!(
+            vec![
+                ("font-file-exists", Some("KosugiMaru-Regular.ttf")),
+                ("primary-language-resolves", None),
+                ("primary-script-resolves", None),
+            ],
+            findings
+                .iter()
+                .map(|f| (f.rule.id, f.font.as_deref()))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn validation_report() {
+        let gf = GoogleFonts::new(PathBuf::from("/this/does/not/exist"), None);
+        let report = gf.validate();
+        assert_eq!(
+            (1, 0, true),
+            (
+                report.count(Severity::Error),
+                report.count(Severity::Warning),
+                report.has_errors()
+            )
+        );
+        assert!(
+            report
+                .to_string()
+                .starts_with("/this/does/not/exist\n  error [metadata-parses] "),
+            "{report}"
+        );
+    }
+
+    #[test]
+    fn rule_ids_are_unique() {
+        let ids = RULES.iter().map(|r| r.id).collect::<HashSet<_>>();
+        assert_eq!(RULES.len(), ids.len());
+    }
+}
@@ -1,4 +1,8 @@
-use std::{collections::HashMap, fs, path::Path};
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
 
 use clap::Parser;
 use embed_anything::embeddings::{
@@ -6,8 +10,7 @@ use embed_anything::embeddings::{
     local::clip::ClipEmbedder,
 };
 use gf_embed::embed_data::EmbedDataProto;
-use gf_metadata::{GoogleFonts, exemplar};
-use home::home_dir;
+use gf_metadata::{ExemplarPreference, GoogleFonts, default_cache_file, select_exemplar};
 use itertools::Itertools;
 use protobuf::Message;
 use regex::Regex;
@@ -24,6 +27,20 @@ struct Args {
     #[arg(long)]
     family_filter: Option<String>,
 
+    /// Path to a google/fonts checkout. If omitted we try $GOOGLE_FONTS_DIR, the config
+    /// file, the current directory and its ancestors then ~/oss/fonts.
+    #[arg(long)]
+    repo_dir: Option<PathBuf>,
+
+    /// Reuse metadata parsed by previous runs, cached in ~/.cache/gf-metadata
+    #[arg(long)]
+    cache: bool,
+
+    /// Which font represents each family: default, static, italic, weight=N or tag=TAG.
+    /// Use the same choice for make_test_images, make_embedding and read-gf-metadata.
+    #[arg(long, default_value = "default")]
+    exemplar: ExemplarPreference,
+
     /// Where to write embedding data to
     #[arg(short, long, default_value = "/tmp/test_data")]
     embed_dir: String,
@@ -64,9 +81,16 @@ fn main() {
         fs::remove_file(entry.path()).expect("To delete files");
     }
 
-    let mut d = home_dir().expect("Must have a home dir");
-    d.push("oss/fonts");
-    let gf = GoogleFonts::new(d, family_filter);
+    let (mut gf, source) = GoogleFonts::discover(args.repo_dir.as_deref(), family_filter)
+        .unwrap_or_else(|e| panic!("{e}"));
+    eprintln!("Reading {:?}, from {source}", gf.repo_dir());
+    if let Some(cache_file) = default_cache_file().filter(|_| args.cache) {
+        gf = gf.with_cache(cache_file);
+    }
+    let exemplar_policy = args
+        .exemplar
+        .policy(&gf)
+        .unwrap_or_else(|e| panic!("Unable to load tags for {}: {e}", args.exemplar));
 
     let mut tags = gf.tags().expect("Tags").iter().collect::<Vec<_>>();
     tags.sort_by_key(|t| (&t.family, &t.tag, (100.0 * t.value) as i32));
@@ -91,7 +115,7 @@ fn main() {
         let mut out = embed_dir.to_path_buf();
         out.push(format!("{}.pb", family.name().replace(" ", "_")));
 
-        let Some(exemplar) = exemplar(family) else {
+        let Some(exemplar) = select_exemplar(family, &*exemplar_policy) else {
             eprintln!("Unable to identify an exemplar for {}", family.name());
             continue;
         };
@@ -128,4 +152,8 @@ fn main() {
             Err(e) => eprintln!("Failed to embed {}: {e:?}", family.name()),
         }
     }
+
+    if let Err(e) = gf.save_cache() {
+        eprintln!("Unable to save cache: {e}");
+    }
 }
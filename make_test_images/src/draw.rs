@@ -1,73 +1,355 @@
-use std::fs::File;
+use std::{fmt, fs::File, io, path::PathBuf};
 
-use gf_metadata::{FontProto, GoogleFonts};
-use harfruzz::{GlyphBuffer, ShaperFont};
+use gf_metadata::{
+    AxisLocation, FontProto, GfMetadataError, GoogleFonts, LanguageProto, SampleTextSelector,
+};
+use harfrust::{
+    Direction, Feature, GlyphBuffer, Language, Script, ShaperData, ShaperInstance, Tag,
+};
 use kurbo::{Affine, BezPath, Point, Vec2};
 use memmap::{Mmap, MmapOptions};
 use skrifa::{
     MetadataProvider,
-    outline::{DrawSettings, OutlinePen},
-    prelude::{LocationRef, Size},
+    instance::Location,
+    metrics::Metrics,
+    outline::{DrawError, DrawSettings, OutlinePen},
+    prelude::{NormalizedCoord, Size},
+    raw::{ReadError, TableProvider},
 };
 
-/// Draws sample text in the specified font.
+use crate::runs::{TextRun, visual_runs};
+
+/// Why sample text couldn't be drawn. Fonts are identified by filename.
+#[derive(Debug)]
+pub enum RenderError {
+    /// The font isn't part of any family we loaded
+    MissingFamily(String),
+    /// No binary could be located for the font
+    MissingFontBinary(GfMetadataError),
+    /// The font binary could not be read
+    Io { path: PathBuf, error: io::Error },
+    /// The font binary isn't a font we can parse
+    NotAFont { path: PathBuf, message: String },
+    /// Shaping produced a glyph the font has no outline for
+    MissingGlyph { font: String, glyph_id: u32 },
+    /// The location names an axis the font doesn't have
+    UnknownAxis { font: String, tag: String },
+    /// The location is outside the range of one of the font's axes
+    AxisOutOfRange {
+        font: String,
+        tag: String,
+        value: f32,
+        min: f32,
+        max: f32,
+    },
+    /// A glyph outline could not be drawn
+    Draw {
+        font: String,
+        glyph_id: u32,
+        error: DrawError,
+    },
+}
+
+impl RenderError {
+    /// A short description of the kind of failure, e.g. to group failures in a report
+    pub fn kind(&self) -> &'static str {
+        match self {
+            RenderError::MissingFamily(..) => "missing family",
+            RenderError::MissingFontBinary(..) => "missing font binary",
+            RenderError::Io { .. } => "unreadable font binary",
+            RenderError::NotAFont { .. } => "not a font",
+            RenderError::MissingGlyph { .. } => "missing glyph outline",
+            RenderError::UnknownAxis { .. } => "unknown axis",
+            RenderError::AxisOutOfRange { .. } => "axis out of range",
+            RenderError::Draw { .. } => "draw failed",
+        }
+    }
+}
+
+impl fmt::Display for RenderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RenderError::MissingFamily(font) => write!(f, "No family contains {font}"),
+            RenderError::MissingFontBinary(error) => write!(f, "{error}"),
+            RenderError::Io { path, error } => write!(f, "Unable to read {path:?}: {error}"),
+            RenderError::NotAFont { path, message } => {
+                write!(f, "Unable to parse {path:?}: {message}")
+            }
+            RenderError::MissingGlyph { font, glyph_id } => {
+                write!(f, "No outline for glyph {glyph_id} in {font}")
+            }
+            RenderError::UnknownAxis { font, tag } => write!(f, "{font} has no {tag} axis"),
+            RenderError::AxisOutOfRange {
+                font,
+                tag,
+                value,
+                min,
+                max,
+            } => write!(f, "{tag} {value} is outside {min}..{max} in {font}"),
+            RenderError::Draw {
+                font,
+                glyph_id,
+                error,
+            } => write!(f, "Unable to draw glyph {glyph_id} of {font}: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for RenderError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            RenderError::MissingFontBinary(error) => Some(error),
+            RenderError::Io { error, .. } => Some(error),
+            RenderError::Draw { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+/// Parses an OpenType feature setting in hb-shape syntax, e.g. smcp, -liga, aalt=2 or
+/// liga[3:5]=0 where the range is byte offsets into the sample text
+pub fn parse_feature(s: &str) -> Result<Feature, String> {
+    s.trim()
+        .parse()
+        .map_err(|e| format!("Invalid feature {s:?}: {e}"))
+}
+
+/// The binary of font, mapped into memory, and where we found it
+fn map_font(gf: &GoogleFonts, font: &FontProto) -> Result<(PathBuf, Mmap), RenderError> {
+    let font_file = gf
+        .find_font_binary(font)
+        .map_err(RenderError::MissingFontBinary)?;
+    let io_error = |error| RenderError::Io {
+        path: font_file.clone(),
+        error,
+    };
+    let fd = File::open(&font_file).map_err(io_error)?;
+    let mmap: Mmap = unsafe { MmapOptions::new().map(&fd).map_err(io_error)? };
+    Ok((font_file, mmap))
+}
+
+/// The stylistic sets, ss01 to ss20, that the GSUB of font has features for, in order
+pub fn stylistic_sets(gf: &GoogleFonts, font: &FontProto) -> Result<Vec<Tag>, RenderError> {
+    let (font_file, mmap) = map_font(gf, font)?;
+    let not_a_font = |e: ReadError| RenderError::NotAFont {
+        path: font_file.clone(),
+        message: e.to_string(),
+    };
+    let font_ref = skrifa::FontRef::new(&mmap).map_err(not_a_font)?;
+    let gsub = match font_ref.gsub() {
+        Ok(gsub) => gsub,
+        Err(ReadError::TableIsMissing(..)) => return Ok(Vec::new()),
+        Err(e) => return Err(not_a_font(e)),
+    };
+    let mut sets = gsub
+        .feature_list()
+        .map_err(not_a_font)?
+        .feature_records()
+        .iter()
+        .map(|r| r.feature_tag().to_be_bytes())
+        .filter(|t| t.starts_with(b"ss") && t[2..].iter().all(u8::is_ascii_digit))
+        .collect::<Vec<_>>();
+    sets.sort();
+    sets.dedup();
+    Ok(sets.iter().map(Tag::new).collect())
+}
+
+/// The metrics of font at location, in font units
+///
+/// Ascent and descent are from OS/2 if USE_TYPO_METRICS is set, otherwise from hhea. Descent
+/// is typically negative.
+pub fn font_metrics(
+    gf: &GoogleFonts,
+    font: &FontProto,
+    location: &AxisLocation,
+) -> Result<Metrics, RenderError> {
+    let (font_file, mmap) = map_font(gf, font)?;
+    let font_ref = skrifa::FontRef::new(&mmap).map_err(|e| RenderError::NotAFont {
+        path: font_file.clone(),
+        message: e.to_string(),
+    })?;
+    let location = font_location(&font_ref, font.filename(), location)?;
+    Ok(font_ref.metrics(Size::unscaled(), &location))
+}
+
+/// The location in font, checked against its fvar. Axes not mentioned are at their default.
+fn font_location(
+    font: &skrifa::FontRef,
+    filename: &str,
+    location: &AxisLocation,
+) -> Result<Location, RenderError> {
+    let axes = font.axes();
+    for (tag, value) in location.iter() {
+        let Some(axis) = axes.iter().find(|a| a.tag() == tag) else {
+            return Err(RenderError::UnknownAxis {
+                font: filename.to_string(),
+                tag: tag.to_string(),
+            });
+        };
+        if !(axis.min_value()..=axis.max_value()).contains(&value) {
+            return Err(RenderError::AxisOutOfRange {
+                font: filename.to_string(),
+                tag: tag.to_string(),
+                value,
+                min: axis.min_value(),
+                max: axis.max_value(),
+            });
+        }
+    }
+    Ok(axes.location(location.iter()))
+}
+
+/// Draws sample text, chosen by selector, in the specified font at location.
+///
+/// The location is applied to both shaping and drawing. The default location draws the
+/// default instance of variable fonts and is the only location static fonts support.
+///
+/// The text is split into runs by direction, see [`visual_runs`], each shaped with the
+/// script and language of the family's primary language where they apply. Features apply
+/// to all of the text unless they have a range, see [`parse_feature`].
 ///
 /// Baseline is at y=0.
-pub fn path_for_sampletext(gf: &GoogleFonts, font: &FontProto) -> BezPath {
+pub fn path_for_sampletext(
+    gf: &GoogleFonts,
+    font: &FontProto,
+    selector: &SampleTextSelector,
+    location: &AxisLocation,
+    features: &[Feature],
+) -> Result<BezPath, RenderError> {
     // Figure out what string to draw
     let Some((_, family)) = gf.family(font) else {
-        panic!("No family available for {font:?}?!");
+        return Err(RenderError::MissingFamily(font.filename().to_string()));
     };
 
-    let lang = gf.primary_language(family);
-    let sample_text = lang.sample_text.styles();
+    let sample_text = selector
+        .select(gf, family)
+        .map(|s| s.text)
+        .unwrap_or_default();
+    let language = gf.primary_language(family).ok().map(|p| p.language);
 
     // Load the font and shape the sample string
-    let Some(font_file) = gf.find_font_binary(font) else {
-        panic!("Unable to locate {font:?}");
+    let (font_file, mmap) = map_font(gf, font)?;
+    let not_a_font = |message: String| RenderError::NotAFont {
+        path: font_file.clone(),
+        message,
     };
+    let harf_font_ref = harfrust::FontRef::new(&mmap).map_err(|e| not_a_font(e.to_string()))?;
+    let skrifa_font_ref = skrifa::FontRef::new(&mmap).map_err(|e| not_a_font(e.to_string()))?;
 
-    let fd = File::open(&font_file).expect("To read fonts!");
-    let mmap: Mmap = unsafe { MmapOptions::new().map(&fd).expect("To map files!") };
-    let harf_font_ref = harfruzz::FontRef::new(&mmap).expect("For font files to be font files!");
-    let skrifa_font_ref = skrifa::FontRef::new(&mmap).expect("Fonts to be fonts");
+    let location = font_location(&skrifa_font_ref, font.filename(), location)?;
 
     // Draw an SVG of it
     let outlines = skrifa_font_ref.outline_glyphs();
     let mut pen = PathPen::default();
 
-    let glyphs = shape(&sample_text, &harf_font_ref);
+    let coords = location.coords();
+    for glyphs in shape_runs(&sample_text, language, features, &harf_font_ref, coords) {
+        draw_glyphs(&glyphs, &outlines, &location, font.filename(), &mut pen)?;
+    }
+
+    Ok(pen.path)
+}
 
+/// Draws shaped glyphs with pen, advancing it past each one
+fn draw_glyphs(
+    glyphs: &GlyphBuffer,
+    outlines: &skrifa::outline::OutlineGlyphCollection,
+    location: &Location,
+    font: &str,
+    pen: &mut PathPen,
+) -> Result<(), RenderError> {
     for (glyph_info, pos) in glyphs.glyph_infos().iter().zip(glyphs.glyph_positions()) {
-        let glyph = outlines
-            .get(glyph_info.glyph_id.into())
-            .expect("Glyphs to exist!");
+        let glyph_id = glyph_info.glyph_id;
+        let Some(glyph) = outlines.get(glyph_id.into()) else {
+            return Err(RenderError::MissingGlyph {
+                font: font.to_string(),
+                glyph_id,
+            });
+        };
+        // Offsets, e.g. to attach marks, are in font units so apply them before the flip
+        let origin = pen.transform;
+        pen.transform = origin
+            * Affine::translate(Vec2 {
+                x: pos.x_offset.into(),
+                y: pos.y_offset.into(),
+            });
         glyph
             .draw(
-                DrawSettings::unhinted(Size::unscaled(), LocationRef::default()),
-                &mut pen,
+                DrawSettings::unhinted(Size::unscaled(), location),
+                &mut *pen,
             )
-            .expect("To draw!");
+            .map_err(|error| RenderError::Draw {
+                font: font.to_string(),
+                glyph_id,
+                error,
+            })?;
 
-        pen.transform = pen.transform.then_translate(Vec2 {
+        pen.transform = origin.then_translate(Vec2 {
             x: pos.x_advance.into(),
             y: pos.y_advance.into(),
         });
     }
+    Ok(())
+}
 
-    pen.path
+/// Shapes text, in language, a buffer per run in the order they are drawn left to right
+fn shape_runs(
+    text: &str,
+    language: Option<&LanguageProto>,
+    features: &[Feature],
+    font: &harfrust::FontRef,
+    coords: &[NormalizedCoord],
+) -> Vec<GlyphBuffer> {
+    visual_runs(text, language)
+        .iter()
+        .map(|run| shape(text, run, &run.features(features), font, coords))
+        .collect()
 }
 
-// Simplified version of <https://github.com/harfbuzz/harfruzz/blob/006472176ab87e3a84e799e74e0ac19fbe943dd7/tests/shaping/main.rs#L107>
-// Will have to update if/when that API updates
-fn shape(text: &str, font: &harfruzz::FontRef) -> GlyphBuffer {
-    let shaper_font = ShaperFont::new(font);
-    let face = shaper_font.shaper(font, &[]);
+// Simplified version of the shaping tests of <https://github.com/harfbuzz/harfrust>
+fn shape(
+    text: &str,
+    run: &TextRun,
+    features: &[Feature],
+    font: &harfrust::FontRef,
+    coords: &[NormalizedCoord],
+) -> GlyphBuffer {
+    let data = ShaperData::new(font);
+    // harfrust and skrifa depend on different versions of read-fonts, convert coords by value
+    let instance = ShaperInstance::from_coords(
+        font,
+        coords
+            .iter()
+            .map(|c| harfrust::NormalizedCoord::from_bits(c.to_bits())),
+    );
+    let shaper = data.shaper(font).instance(Some(&instance)).build();
 
-    let mut buffer = harfruzz::UnicodeBuffer::new();
-    buffer.push_str(text);
+    let mut buffer = harfrust::UnicodeBuffer::new();
+    buffer.push_str(&text[run.range.clone()]);
+    buffer.set_direction(if run.rtl {
+        Direction::RightToLeft
+    } else {
+        Direction::LeftToRight
+    });
+    if let Some(script) = run
+        .script
+        .as_deref()
+        .and_then(|s| s.parse::<Tag>().ok())
+        .and_then(Script::from_iso15924_tag)
+    {
+        buffer.set_script(script);
+    }
+    if let Some(language) = run
+        .language
+        .as_deref()
+        .and_then(|l| l.parse::<Language>().ok())
+    {
+        buffer.set_language(language);
+    }
+    // Fills in whatever the run didn't specify
+    buffer.guess_segment_properties();
 
-    harfruzz::shape(&face, &[], buffer)
+    shaper.shape(buffer, features)
 }
 
 struct PathPen {
@@ -145,3 +427,171 @@ impl OutlinePen for PathPen {
         self.path.close_path();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use gf_metadata::read_language;
+    use write_fonts::{
+        FontBuilder,
+        tables::{
+            cmap::Cmap,
+            gsub::{Gsub, SingleSubst, SingleSubstFormat2, SubstitutionLookup},
+            head::{Flags, Head, MacStyle},
+            hhea::Hhea,
+            hmtx::{Hmtx, LongMetric},
+            layout::{
+                Feature as OtFeature, FeatureList, FeatureRecord, LangSys, LangSysRecord, Lookup,
+                LookupFlag, LookupList, Script as OtScript, ScriptList, ScriptRecord,
+            },
+            maxp::Maxp,
+        },
+        types::{FWord, Fixed, GlyphId, GlyphId16, LongDateTime, Tag as OtTag, UfWord},
+    };
+
+    use super::*;
+
+    const NUM_GLYPHS: u16 = 11;
+
+    /// A font that shapes, but can't draw, a little Latin, Arabic and Devanagari
+    ///
+    /// Glyphs are .notdef, space, a, b, beh, beh.init, beh.medi, beh.fina, ka, i-matra and
+    /// ka.marathi. Under arab init, medi and fina pick the joining forms of beh and under
+    /// dev2 the Marathi locl picks ka.marathi.
+    fn shaping_font() -> Vec<u8> {
+        let cmap = Cmap::from_mappings(
+            [
+                (' ', 1),
+                ('a', 2),
+                ('b', 3),
+                ('\u{0628}', 4),
+                ('\u{0915}', 8),
+                ('\u{093F}', 9),
+            ]
+            .map(|(c, g)| (c, GlyphId::new(g))),
+        )
+        .unwrap();
+        let single = |from: u16, to: u16| -> SubstitutionLookup {
+            let subst = SingleSubstFormat2::new(
+                [GlyphId16::new(from)].into_iter().collect(),
+                vec![GlyphId16::new(to)],
+            );
+            Lookup::new(LookupFlag::empty(), vec![SingleSubst::from(subst)]).into()
+        };
+        // A lookup per feature, both in feature tag order
+        let lookups = LookupList::new(vec![
+            single(4, 7),
+            single(4, 5),
+            single(8, 10),
+            single(4, 6),
+        ]);
+        let features = FeatureList::new(
+            [b"fina", b"init", b"locl", b"medi"]
+                .into_iter()
+                .enumerate()
+                .map(|(i, tag)| {
+                    FeatureRecord::new(OtTag::new(tag), OtFeature::new(None, vec![i as u16]))
+                })
+                .collect(),
+        );
+        let scripts = ScriptList::new(vec![
+            ScriptRecord::new(
+                OtTag::new(b"arab"),
+                OtScript::new(Some(LangSys::new(vec![0, 1, 3])), vec![]),
+            ),
+            ScriptRecord::new(
+                OtTag::new(b"dev2"),
+                OtScript::new(
+                    Some(LangSys::new(vec![])),
+                    vec![LangSysRecord::new(
+                        OtTag::new(b"MAR "),
+                        LangSys::new(vec![2]),
+                    )],
+                ),
+            ),
+        ]);
+        let head = Head::new(
+            Fixed::ONE,
+            0,
+            Flags::empty(),
+            1000,
+            LongDateTime::new(0),
+            LongDateTime::new(0),
+            0,
+            0,
+            0,
+            0,
+            MacStyle::empty(),
+            0,
+            0,
+        );
+        let hhea = Hhea::new(
+            FWord::new(800),
+            FWord::new(-200),
+            FWord::new(0),
+            UfWord::new(500),
+            FWord::new(0),
+            FWord::new(0),
+            FWord::new(0),
+            1,
+            0,
+            0,
+            NUM_GLYPHS,
+        );
+        let hmtx = Hmtx::new(
+            (0..NUM_GLYPHS).map(|_| LongMetric::new(500, 0)).collect(),
+            vec![],
+        );
+        FontBuilder::new()
+            .add_table(&head)
+            .unwrap()
+            .add_table(&hhea)
+            .unwrap()
+            .add_table(&hmtx)
+            .unwrap()
+            .add_table(&Maxp::new(NUM_GLYPHS))
+            .unwrap()
+            .add_table(&cmap)
+            .unwrap()
+            .add_table(&Gsub::new(scripts, features, lookups))
+            .unwrap()
+            .build()
+    }
+
+    fn language(id: &str) -> LanguageProto {
+        let (lang, script) = id.split_once('_').unwrap();
+        read_language(&format!(
+            "id: \"{id}\"\nlanguage: \"{lang}\"\nscript: \"{script}\"\n"
+        ))
+        .unwrap()
+    }
+
+    /// The glyphs of text, written in language, left to right as drawn
+    fn glyph_ids(text: &str, language_id: &str) -> Vec<u32> {
+        let data = shaping_font();
+        let font = harfrust::FontRef::new(&data).unwrap();
+        let language = language(language_id);
+        shape_runs(text, Some(&language), &[], &font, &[])
+            .iter()
+            .flat_map(|g| g.glyph_infos().iter().map(|i| i.glyph_id))
+            .collect::<Vec<_>>()
+    }
+
+    #[test]
+    fn arabic_shaping() {
+        // Joined forms, drawn right to left: fina, medi, init
+        assert_eq!(vec![7, 6, 5], glyph_ids("ببب", "ar_Arab"));
+
+        // Latin within Arabic stays left to right, the runs are placed right to left
+        assert_eq!(vec![7, 6, 5, 1, 2, 3], glyph_ids("ab ببب", "ar_Arab"));
+        assert_eq!(vec![2, 3, 1, 7, 6, 5], glyph_ids("ببب ab", "ar_Arab"));
+    }
+
+    #[test]
+    fn devanagari_shaping() {
+        // The i-matra is drawn before the consonant it follows
+        assert_eq!(vec![9, 8], glyph_ids("कि", "hi_Deva"));
+
+        // The language picks Marathi forms
+        assert_eq!(vec![9, 10], glyph_ids("कि", "mr_Deva"));
+    }
+}
@@ -4,6 +4,7 @@ use png::EncodingError;
 use tiny_skia::{Color, FillRule, Paint, PathBuilder, Pixmap, Transform};
 
 pub mod draw;
+pub mod runs;
 
 trait ToPixmapColor {
     fn to_pixmap_color(&self) -> Color;
@@ -1,17 +1,53 @@
 use std::{
-    fs,
+    collections::BTreeMap,
+    fmt, fs, io,
     path::{Path, PathBuf},
+    str::FromStr,
 };
 
 use clap::Parser;
-use color::parse_color;
-use gf_metadata::{FontProto, GoogleFonts, exemplar};
-use home::home_dir;
+use color::{DynamicColor, parse_color};
+use gf_metadata::{
+    AxisGrid, AxisLocation, ExemplarPreference, FontProto, GoogleFonts, GridAxis,
+    SampleTextPurpose, SampleTextSelector, default_cache_file, is_variable, select_exemplar,
+};
+use harfrust::{Feature, Tag};
 use kurbo::{Affine, BezPath, Rect, Shape, Vec2};
-use make_test_images::{draw::path_for_sampletext, draw_png};
+use make_test_images::{
+    draw::{RenderError, font_metrics, parse_feature, path_for_sampletext, stylistic_sets},
+    draw_png,
+};
+use png::EncodingError;
 use regex::Regex;
+use skrifa::metrics::Metrics;
 use tiny_skia::Pixmap;
 
+/// The height of png test images in pixels
+const PNG_HEIGHT: f64 = 128.0;
+
+/// How to size sample text in png test images
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+enum Scale {
+    /// Scale each sample so the ink is exactly 128px high
+    #[default]
+    Bbox,
+    /// Scale every font to the same pixels per em with the baseline at the same height, so
+    /// relative size and proportions are preserved
+    Em,
+}
+
+impl FromStr for Scale {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "bbox" => Ok(Scale::Bbox),
+            "em" => Ok(Scale::Em),
+            _ => Err(format!("Unknown scale {s:?}, expected bbox or em")),
+        }
+    }
+}
+
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
 struct Args {
@@ -34,6 +70,59 @@ struct Args {
     /// Family path filter, retain only paths that contain this regex.
     #[arg(long)]
     family_filter: Option<String>,
+
+    /// Path to a google/fonts checkout. If omitted we try $GOOGLE_FONTS_DIR, the config
+    /// file, the current directory and its ancestors then ~/oss/fonts.
+    #[arg(long)]
+    repo_dir: Option<PathBuf>,
+
+    /// Reuse metadata parsed by previous runs, cached in ~/.cache/gf-metadata
+    #[arg(long)]
+    cache: bool,
+
+    /// Which font represents each family: default, static, italic, weight=N or tag=TAG.
+    /// Use the same choice for make_test_images, make_embedding and read-gf-metadata.
+    #[arg(long, default_value = "default")]
+    exemplar: ExemplarPreference,
+
+    /// What kind of sample text to draw: masthead, styles, paragraph or poster
+    #[arg(long, default_value = "styles")]
+    sample_text: SampleTextPurpose,
+
+    /// Prefer sample text of at most this many characters, truncating if nothing fits
+    #[arg(long)]
+    max_chars: Option<usize>,
+
+    /// Draw variable exemplars at every combination of these axis values rather than at
+    /// their default location, e.g. --grid wght=100..900:100 --grid wdth=min,max. Values
+    /// are numbers, min, default, max, named or start..end:step.
+    #[arg(long)]
+    grid: Vec<GridAxis>,
+
+    /// OpenType features to apply, e.g. --feature smcp,ss01,-liga. A range restricts a
+    /// feature to those bytes of the sample text, e.g. liga[3:5]=0.
+    #[arg(long, value_delimiter = ',', value_parser = parse_feature, allow_hyphen_values = true)]
+    feature: Vec<Feature>,
+
+    /// Also draw an image with each stylistic set, ss01 to ss20, the exemplar's GSUB has
+    #[arg(long)]
+    stylistic_sets: bool,
+
+    /// How to size text in pngs: bbox fits the ink of each sample to 128px high, em gives
+    /// every font the same pixels per em and baseline position
+    #[arg(long, default_value = "bbox")]
+    scale: Scale,
+
+    /// With --scale em, how far images extend above the baseline, in em. Defaults to the
+    /// tallest ascender of the fonts being drawn; set it to keep the baseline in the same
+    /// place across runs.
+    #[arg(long)]
+    em_ascent: Option<f64>,
+
+    /// With --scale em, how far images extend below the baseline, in em. Defaults to the
+    /// deepest descender of the fonts being drawn.
+    #[arg(long)]
+    em_descent: Option<f64>,
 }
 
 fn svg(sample: &BezPath, viewbox: Rect) -> String {
@@ -51,9 +140,46 @@ fn svg(sample: &BezPath, viewbox: Rect) -> String {
     svg
 }
 
-fn output_file(dir: &str, exemplar: &FontProto, ext: &str) -> PathBuf {
+/// One image of an exemplar: where in the designspace and what stylistic set, if any
+struct Variant {
+    location: AxisLocation,
+    stylistic_set: Option<Tag>,
+}
+
+impl Variant {
+    fn is_default(&self) -> bool {
+        self.location.is_default() && self.stylistic_set.is_none()
+    }
+
+    /// The features requested on the command line plus our stylistic set
+    fn features(&self, features: &[Feature]) -> Vec<Feature> {
+        let mut features = features.to_vec();
+        if let Some(tag) = self.stylistic_set {
+            features.push(Feature::new(tag, 1, ..));
+        }
+        features
+    }
+}
+
+impl fmt::Display for Variant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parts = [
+            (!self.location.is_default()).then(|| self.location.to_string()),
+            self.stylistic_set.map(|t| t.to_string()),
+        ];
+        let parts = parts.into_iter().flatten().collect::<Vec<_>>();
+        write!(f, "{}", parts.join("-"))
+    }
+}
+
+/// The default variant keeps the plain name make_embedding expects
+fn output_file(dir: &str, exemplar: &FontProto, variant: &Variant, ext: &str) -> PathBuf {
     let mut out_file = PathBuf::from(dir);
-    out_file.push(format!("{}{ext}", exemplar.filename()));
+    if variant.is_default() {
+        out_file.push(format!("{}{ext}", exemplar.filename()));
+    } else {
+        out_file.push(format!("{}-{variant}{ext}", exemplar.filename()));
+    }
     out_file
 }
 
@@ -62,11 +188,287 @@ fn with_margin(rect: Rect, multiplier: f64) -> Rect {
     rect.inflate(margin, margin)
 }
 
+/// How far images drawn with em scaling extend above and below the baseline, in em
+///
+/// Every image is PNG_HEIGHT high with the baseline the same distance from the top.
+#[derive(Copy, Clone, Debug, PartialEq)]
+struct EmFrame {
+    ascent: f64,
+    descent: f64,
+}
+
+impl EmFrame {
+    /// The frame --em-ascent and --em-descent ask for, fitting metrics on any side they don't
+    ///
+    /// metrics are only consumed if a side is missing. Frames with no height are an error.
+    fn new(
+        ascent: Option<f64>,
+        descent: Option<f64>,
+        metrics: impl IntoIterator<Item = Metrics>,
+    ) -> Result<Self, String> {
+        let frame = match (ascent, descent) {
+            (Some(ascent), Some(descent)) => EmFrame { ascent, descent },
+            (ascent, descent) => {
+                let Some(fitting) = EmFrame::fitting(metrics) else {
+                    return Err(
+                        "No font metrics to fit --scale em to, pass --em-ascent and --em-descent"
+                            .to_string(),
+                    );
+                };
+                EmFrame {
+                    ascent: ascent.unwrap_or(fitting.ascent),
+                    descent: descent.unwrap_or(fitting.descent),
+                }
+            }
+        };
+        let height = frame.ascent + frame.descent;
+        if !height.is_finite() || height <= 0.0 {
+            return Err(format!(
+                "--scale em needs a frame with some height, not {frame}"
+            ));
+        }
+        Ok(frame)
+    }
+
+    /// The smallest frame that holds the ascender and descender of every font, None if
+    /// there are no fonts with a units per em
+    ///
+    /// Ascender and descender are from OS/2 if USE_TYPO_METRICS is set, otherwise hhea.
+    fn fitting(metrics: impl IntoIterator<Item = Metrics>) -> Option<Self> {
+        metrics
+            .into_iter()
+            .filter(|m| m.units_per_em > 0)
+            .map(|m| {
+                let upem = f64::from(m.units_per_em);
+                EmFrame {
+                    ascent: f64::from(m.ascent) / upem,
+                    descent: f64::from(-m.descent) / upem,
+                }
+            })
+            .reduce(|a, b| EmFrame {
+                ascent: a.ascent.max(b.ascent),
+                descent: a.descent.max(b.descent),
+            })
+    }
+
+    /// The frame in font units, horizontally around ink, along with the scale that makes
+    /// it PNG_HEIGHT high
+    fn around(&self, ink: Rect, units_per_em: u16) -> (Rect, f64) {
+        let upem = f64::from(units_per_em);
+        // 3% of the height as margin, as in bbox mode, but the same for every font
+        let margin = (self.ascent + self.descent) * upem * 0.03;
+        // y is down so above the baseline is negative
+        let frame = Rect::new(
+            ink.min_x() - margin,
+            -self.ascent * upem,
+            ink.max_x() + margin,
+            self.descent * upem,
+        );
+        (frame, PNG_HEIGHT / frame.height())
+    }
+
+    /// The baseline, in pixels from the top of the png
+    fn baseline(&self) -> f64 {
+        PNG_HEIGHT * self.ascent / (self.ascent + self.descent)
+    }
+}
+
+impl fmt::Display for EmFrame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:.3} em above to {:.3} em below the baseline",
+            self.ascent, self.descent
+        )
+    }
+}
+
 fn ensure_has_dir(dir: &str) {
     let p = Path::new(dir);
     fs::create_dir_all(p).expect("To create output dir");
 }
 
+/// Why a family has no test image
+#[derive(Debug)]
+enum Failure {
+    NoExemplar,
+    Render(RenderError),
+    NothingDrawn,
+    /// With em scaling, ink reaching this far above and below the baseline, in em, doesn't
+    /// fit the frame
+    Clipped {
+        ascent: f64,
+        descent: f64,
+        frame: EmFrame,
+    },
+    Pixmap(Rect),
+    Png(EncodingError),
+    Write {
+        path: PathBuf,
+        error: io::Error,
+    },
+}
+
+impl Failure {
+    fn kind(&self) -> &'static str {
+        match self {
+            Failure::NoExemplar => "no exemplar",
+            Failure::Render(e) => e.kind(),
+            Failure::NothingDrawn => "nothing drawn",
+            Failure::Clipped { .. } => "clipped",
+            Failure::Pixmap(..) => "pixmap allocation",
+            Failure::Png(..) => "png encoding",
+            Failure::Write { .. } => "write failed",
+        }
+    }
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Failure::NoExemplar => write!(f, "Unable to identify an exemplar"),
+            Failure::Render(e) => write!(f, "{e}"),
+            Failure::NothingDrawn => write!(f, "Nothing drawn (area 0)"),
+            Failure::Clipped {
+                ascent,
+                descent,
+                frame,
+            } => write!(
+                f,
+                "Ink from {ascent:.3} em above to {descent:.3} em below the baseline doesn't fit \
+                 {frame}"
+            ),
+            Failure::Pixmap(rect) => write!(f, "Failed to allocate {rect:?} pixmap"),
+            Failure::Png(e) => write!(f, "Unable to encode png: {e}"),
+            Failure::Write { path, error } => write!(f, "Unable to write {path:?}: {error}"),
+        }
+    }
+}
+
+fn write_output(path: PathBuf, contents: impl AsRef<[u8]>) -> Result<(), Failure> {
+    fs::write(&path, contents).map_err(|error| Failure::Write {
+        path: path.clone(),
+        error,
+    })?;
+    eprintln!("Wrote {path:?}");
+    Ok(())
+}
+
+/// Write the svg and png for a variant of exemplar, with em scaling if em_frame is set
+#[allow(clippy::too_many_arguments)]
+fn draw_exemplar(
+    args: &Args,
+    gf: &GoogleFonts,
+    exemplar: &FontProto,
+    variant: &Variant,
+    em_frame: Option<EmFrame>,
+    selector: &SampleTextSelector,
+    text_color: DynamicColor,
+    backdrop_color: DynamicColor,
+) -> Result<(), Failure> {
+    let path = path_for_sampletext(
+        gf,
+        exemplar,
+        selector,
+        &variant.location,
+        &variant.features(&args.feature),
+    )
+    .map_err(Failure::Render)?;
+
+    // Add a 3% of smallest dimension as margin
+    let sample_bbox = with_margin(path.bounding_box(), 0.03);
+
+    if sample_bbox.area() == 0.0 {
+        return Err(Failure::NothingDrawn);
+    }
+
+    // What to draw, in font units, and how much to scale it by to make a png
+    let (frame, scale) = match em_frame {
+        None => (sample_bbox, PNG_HEIGHT / path.bounding_box().height()),
+        Some(em_frame) => {
+            let metrics = font_metrics(gf, exemplar, &variant.location).map_err(Failure::Render)?;
+            let ink = path.bounding_box();
+            let (frame, scale) = em_frame.around(ink, metrics.units_per_em);
+            // Don't write an image we know is missing ink
+            if ink.min_y() < frame.min_y() || ink.max_y() > frame.max_y() {
+                let upem = f64::from(metrics.units_per_em);
+                return Err(Failure::Clipped {
+                    ascent: -ink.min_y() / upem,
+                    descent: ink.max_y() / upem,
+                    frame: em_frame,
+                });
+            }
+            (frame, scale)
+        }
+    };
+
+    // Draw an svg
+    let svg = svg(&path, frame);
+    write_output(output_file(&args.svg_dir, exemplar, variant, ".svg"), svg)?;
+
+    // Draw a png, moving the frame so minx/y are both 0 and scaling it to PNG_HEIGHT
+    let mut scaled_path = path.clone();
+    let transform = Affine::translate(Vec2 {
+        x: -frame.min_x(),
+        y: -frame.min_y(),
+    })
+    .then_scale(scale);
+    scaled_path.apply_affine(transform);
+    let scaled_frame = transform.transform_rect_bbox(frame);
+    let mut pixmap = Pixmap::new(
+        scaled_frame.width().ceil() as u32,
+        scaled_frame.height().ceil() as u32,
+    )
+    .ok_or(Failure::Pixmap(scaled_frame))?;
+    let png =
+        draw_png(&mut pixmap, text_color, backdrop_color, scaled_path).map_err(Failure::Png)?;
+    write_output(output_file(&args.png_dir, exemplar, variant, ".png"), png)
+}
+
+/// A table of the families, and variants, that failed followed by a count of each kind
+/// of failure
+fn print_failures(failures: &[(String, Failure)], attempted: usize) {
+    println!(
+        "Drew {}/{attempted} test images successfully",
+        attempted - failures.len()
+    );
+    if failures.is_empty() {
+        return;
+    }
+    let family_width = failures
+        .iter()
+        .map(|(family, _)| family.chars().count())
+        .chain(["Family".len()])
+        .max()
+        .unwrap_or_default();
+    let kind_width = failures
+        .iter()
+        .map(|(_, failure)| failure.kind().len())
+        .chain(["Reason".len()])
+        .max()
+        .unwrap_or_default();
+    println!(
+        "{:family_width$}  {:kind_width$}  Detail",
+        "Family", "Reason"
+    );
+    for (family, failure) in failures {
+        println!(
+            "{family:family_width$}  {:kind_width$}  {failure}",
+            failure.kind()
+        );
+    }
+
+    let mut by_kind = BTreeMap::new();
+    for (_, failure) in failures {
+        *by_kind.entry(failure.kind()).or_insert(0) += 1;
+    }
+    let by_kind = by_kind
+        .into_iter()
+        .map(|(kind, count)| format!("{count} {kind}"))
+        .collect::<Vec<_>>();
+    println!("Failures: {}", by_kind.join(", "));
+}
+
 fn main() {
     let args = Args::parse();
 
@@ -74,14 +476,27 @@ fn main() {
     let backdrop_color = parse_color(&args.backdrop_color).unwrap();
     let family_filter = args
         .family_filter
-        .map(|f| Regex::new(&f).expect("A valid filter regex"));
+        .as_deref()
+        .map(|f| Regex::new(f).expect("A valid filter regex"));
 
     ensure_has_dir(&args.svg_dir);
     ensure_has_dir(&args.png_dir);
 
-    let mut d = home_dir().expect("Must have a home dir");
-    d.push("oss/fonts");
-    let gf = GoogleFonts::new(d, family_filter);
+    let (mut gf, source) = GoogleFonts::discover(args.repo_dir.as_deref(), family_filter)
+        .unwrap_or_else(|e| panic!("{e}"));
+    eprintln!("Reading {:?}, from {source}", gf.repo_dir());
+    if let Some(cache_file) = default_cache_file().filter(|_| args.cache) {
+        gf = gf.with_cache(cache_file);
+    }
+    let exemplar_policy = args
+        .exemplar
+        .policy(&gf)
+        .unwrap_or_else(|e| panic!("Unable to load tags for {}: {e}", args.exemplar));
+
+    let mut selector = SampleTextSelector::new(args.sample_text);
+    if let Some(max_chars) = args.max_chars {
+        selector = selector.max_chars(max_chars);
+    }
 
     let mut metadatas = Vec::new();
     let mut metadata_fail = 0;
@@ -102,53 +517,190 @@ fn main() {
         metadatas.len() + metadata_fail
     );
 
+    // Work out every image we're going to draw
+    let grid = AxisGrid::new(args.grid.clone());
+    let mut attempted = 0;
+    let mut failures = Vec::new();
+    let mut jobs = Vec::new();
     for metadata in &metadatas {
-        let Some(exemplar) = exemplar(metadata) else {
-            eprintln!("Unable to identify an exemplar for {}", metadata.name());
+        let Some(exemplar) = select_exemplar(metadata, &*exemplar_policy) else {
+            eprintln!("{} for {}", Failure::NoExemplar, metadata.name());
+            attempted += 1;
+            failures.push((metadata.name().to_string(), Failure::NoExemplar));
             continue;
         };
-        let path = path_for_sampletext(&gf, exemplar);
+        let locations = if is_variable(metadata, exemplar) {
+            grid.locations(&gf.family_axes(metadata))
+        } else {
+            vec![AxisLocation::default()]
+        };
+        let mut sets = vec![None];
+        if args.stylistic_sets {
+            match stylistic_sets(&gf, exemplar) {
+                Ok(tags) => sets.extend(tags.into_iter().map(Some)),
+                Err(e) => eprintln!("Unable to read stylistic sets of {}: {e}", metadata.name()),
+            }
+        }
+        for location in locations {
+            for stylistic_set in sets.iter() {
+                let variant = Variant {
+                    location: location.clone(),
+                    stylistic_set: *stylistic_set,
+                };
+                jobs.push((metadata, exemplar, variant));
+            }
+        }
+    }
 
-        // Add a 3% of smallest dimension as margin
-        let sample_bbox = with_margin(path.bounding_box(), 0.03);
+    // Em scaling uses the same frame for everything so the baseline doesn't move
+    let em_frame = (args.scale == Scale::Em).then(|| {
+        let em_frame = EmFrame::new(
+            args.em_ascent,
+            args.em_descent,
+            jobs.iter()
+                .filter(|(_, _, variant)| variant.stylistic_set.is_none())
+                .filter_map(|(_, exemplar, variant)| {
+                    font_metrics(&gf, exemplar, &variant.location).ok()
+                }),
+        )
+        .unwrap_or_else(|e| panic!("{e}"));
+        eprintln!(
+            "Drawing {em_frame}, the baseline {:.1}px from the top",
+            em_frame.baseline()
+        );
+        em_frame
+    });
 
-        if sample_bbox.area() == 0.0 {
-            eprintln!("Nothing drawn (area 0) for {}", metadata.name());
-            continue;
+    for (metadata, exemplar, variant) in jobs {
+        attempted += 1;
+        let drawn = draw_exemplar(
+            &args,
+            &gf,
+            exemplar,
+            &variant,
+            em_frame,
+            &selector,
+            text_color,
+            backdrop_color,
+        );
+        if let Err(failure) = drawn {
+            let what = if variant.is_default() {
+                metadata.name().to_string()
+            } else {
+                format!("{} {variant}", metadata.name())
+            };
+            eprintln!("{failure} for {what}");
+            failures.push((what, failure));
         }
+    }
 
-        // Draw an svg
-        let svg = svg(&path, sample_bbox);
-        let svg_out = output_file(&args.svg_dir, exemplar, ".svg");
-        fs::write(&svg_out, svg).expect("To write output files");
-        eprintln!("Wrote {svg_out:?}");
-
-        // Draw a png normalized to fit within 128 vertical pixels
-        let mut scaled_path = path.clone();
-        // Move the bbox so minx/y are both 0 and scale so height is 128
-        // TODO: this ignores font choice of vertical height, perhaps we should scale everything the same
-        // e.g. apply the scale that makes the largest height fit
-        let transform = Affine::translate(Vec2 {
-            x: -sample_bbox.min_x(),
-            y: -sample_bbox.min_y(),
-        })
-        .then_scale(128.0 / path.bounding_box().height());
-        scaled_path.apply_affine(transform);
-        let scaled_bbox = with_margin(scaled_path.bounding_box(), 0.03);
-        let mut pixmap = Pixmap::new(
-            scaled_bbox.width().ceil() as u32,
-            scaled_bbox.height().ceil() as u32,
-        )
-        .unwrap_or_else(|| {
-            panic!(
-                "Failed to allocate {scaled_bbox:?} pixmap for {}",
-                metadata.name()
-            )
-        });
-        let png =
-            draw_png(&mut pixmap, text_color, backdrop_color, scaled_path).expect("To draw png");
-        let png_out = output_file(&args.png_dir, exemplar, ".png");
-        fs::write(&png_out, png).expect("To write output files");
-        eprintln!("Wrote {png_out:?}");
+    if let Err(e) = gf.save_cache() {
+        eprintln!("Unable to save cache: {e}");
+    }
+
+    print_failures(&failures, attempted);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metrics(units_per_em: u16, ascent: f32, descent: f32) -> Metrics {
+        Metrics {
+            units_per_em,
+            ascent,
+            descent,
+            ..Default::default()
+        }
+    }
+
+    /// Metrics that mustn't be looked at
+    fn unreachable_metrics() -> impl Iterator<Item = Metrics> {
+        std::iter::from_fn(|| panic!("Metrics consumed"))
+    }
+
+    #[test]
+    fn fitting_takes_the_extremes() {
+        assert_eq!(
+            Some(EmFrame {
+                ascent: 1.0,
+                descent: 0.25
+            }),
+            EmFrame::fitting([
+                metrics(1000, 800.0, -200.0),
+                metrics(2048, 2048.0, -512.0),
+                metrics(0, 5000.0, -5000.0),
+            ])
+        );
+    }
+
+    #[test]
+    fn fitting_nothing() {
+        assert_eq!(None, EmFrame::fitting([]));
+        assert_eq!(None, EmFrame::fitting([metrics(0, 800.0, -200.0)]));
+    }
+
+    #[test]
+    fn frame_from_args() {
+        let frame = EmFrame {
+            ascent: 1.5,
+            descent: 0.5,
+        };
+        assert_eq!(
+            Ok(frame),
+            EmFrame::new(Some(1.5), Some(0.5), unreachable_metrics())
+        );
+        assert_eq!(
+            Ok(frame),
+            EmFrame::new(Some(1.5), None, [metrics(1000, 800.0, -500.0)])
+        );
+        assert_eq!(
+            Ok(frame),
+            EmFrame::new(None, Some(0.5), [metrics(1000, 1500.0, -200.0)])
+        );
+    }
+
+    #[test]
+    fn frame_without_height() {
+        // No jobs, or metrics failed for every one
+        assert_eq!(
+            Err(
+                "No font metrics to fit --scale em to, pass --em-ascent and --em-descent"
+                    .to_string()
+            ),
+            EmFrame::new(None, None, [])
+        );
+        assert_eq!(
+            Err(
+                "No font metrics to fit --scale em to, pass --em-ascent and --em-descent"
+                    .to_string()
+            ),
+            EmFrame::new(Some(1.0), None, [])
+        );
+        assert!(EmFrame::new(None, None, [metrics(1000, 0.0, 0.0)]).is_err());
+        assert!(EmFrame::new(Some(0.0), Some(0.0), unreachable_metrics()).is_err());
+        assert!(EmFrame::new(Some(f64::NAN), Some(0.5), unreachable_metrics()).is_err());
+    }
+
+    #[test]
+    fn frame_around_ink() {
+        let frame = EmFrame {
+            ascent: 1.0,
+            descent: 0.25,
+        };
+        // 3% of 1250 units of margin either side of the ink
+        assert_eq!(
+            (Rect::new(62.5, -1000.0, 637.5, 250.0), PNG_HEIGHT / 1250.0),
+            frame.around(Rect::new(100.0, -700.0, 600.0, 200.0), 1000)
+        );
+    }
+
+    #[test]
+    fn baseline() {
+        let frame = EmFrame {
+            ascent: 1.0,
+            descent: 0.25,
+        };
+        assert_eq!(102.4, frame.baseline());
     }
 }
@@ -0,0 +1,193 @@
+//! Splitting sample text into runs that can each be shaped with one direction and script.
+
+use std::ops::Range;
+
+use gf_metadata::LanguageProto;
+use harfrust::Feature;
+use unicode_bidi::{BidiInfo, Level};
+
+/// ISO 15924 ids of the scripts written right to left
+const RTL_SCRIPTS: &[&str] = &[
+    "Adlm", "Arab", "Armi", "Avst", "Chrs", "Cprt", "Elym", "Hatr", "Hebr", "Hung", "Khar", "Lydi",
+    "Mand", "Mani", "Mend", "Merc", "Mero", "Narb", "Nbat", "Nkoo", "Orkh", "Ougr", "Palm", "Phli",
+    "Phlp", "Phnx", "Prti", "Rohg", "Samr", "Sarb", "Sogd", "Sogo", "Syrc", "Thaa", "Yezi",
+];
+
+/// Whether the script with this ISO 15924 id, e.g. Arab, is written right to left
+pub fn is_rtl_script(script: &str) -> bool {
+    RTL_SCRIPTS.contains(&script)
+}
+
+/// A run of text to shape with a single direction, script and language
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextRun {
+    /// The byte range of the run in the text
+    pub range: Range<usize>,
+    pub rtl: bool,
+    /// ISO 15924 id, e.g. Arab, None to have the shaper guess
+    pub script: Option<String>,
+    /// BCP 47 language tag, e.g. ar, None to have the shaper guess
+    pub language: Option<String>,
+}
+
+impl TextRun {
+    /// The features that apply to this run, with ranges relative to its start
+    ///
+    /// Feature ranges are byte offsets into the whole text, u32::MAX meaning the end.
+    pub fn features(&self, features: &[Feature]) -> Vec<Feature> {
+        let (start, end) = (self.range.start as u32, self.range.end as u32);
+        features
+            .iter()
+            .filter(|f| f.start < end && f.end > start)
+            .map(|f| Feature {
+                tag: f.tag,
+                value: f.value,
+                start: f.start.saturating_sub(start),
+                end: if f.end == u32::MAX {
+                    u32::MAX
+                } else {
+                    f.end.min(end) - start
+                },
+            })
+            .collect()
+    }
+}
+
+/// The runs of text, written in language, in the order they are drawn left to right
+///
+/// Bidi resolution splits each paragraph into runs of a single direction. The paragraph
+/// direction, script and language come from language. A run against the direction of that
+/// script, e.g. Latin in Arabic sample text, is in some other script so the shaper guesses.
+pub fn visual_runs(text: &str, language: Option<&LanguageProto>) -> Vec<TextRun> {
+    let script = language.filter(|l| l.has_script()).map(|l| l.script());
+    let paragraph_level = script.map(|s| {
+        if is_rtl_script(s) {
+            Level::rtl()
+        } else {
+            Level::ltr()
+        }
+    });
+    let bidi = BidiInfo::new(text, paragraph_level);
+
+    let mut runs = Vec::new();
+    for paragraph in bidi.paragraphs.iter() {
+        let (levels, ranges) = bidi.visual_runs(paragraph, paragraph.range.clone());
+        for range in ranges {
+            let rtl = levels[range.start].is_rtl();
+            let in_script = script.filter(|s| is_rtl_script(s) == rtl);
+            runs.push(TextRun {
+                range,
+                rtl,
+                script: in_script.map(str::to_string),
+                language: language
+                    .filter(|l| in_script.is_some() && l.has_language())
+                    .map(|l| l.language().to_string()),
+            });
+        }
+    }
+    runs
+}
+
+#[cfg(test)]
+mod tests {
+    use gf_metadata::read_language;
+
+    use super::*;
+
+    fn language(id: &str) -> LanguageProto {
+        let (lang, script) = id.split_once('_').unwrap();
+        read_language(&format!(
+            "id: \"{id}\"\nlanguage: \"{lang}\"\nscript: \"{script}\"\n"
+        ))
+        .unwrap()
+    }
+
+    fn runs<'a>(
+        text: &'a str,
+        language: Option<&LanguageProto>,
+    ) -> Vec<(&'a str, bool, Option<String>)> {
+        visual_runs(text, language)
+            .into_iter()
+            .map(|r| (&text[r.range], r.rtl, r.script))
+            .collect()
+    }
+
+    #[test]
+    fn arabic_sample() {
+        let ar = language("ar_Arab");
+        let text = "مرحبا بالعالم";
+        assert_eq!(
+            vec![TextRun {
+                range: 0..text.len(),
+                rtl: true,
+                script: Some("Arab".to_string()),
+                language: Some("ar".to_string()),
+            }],
+            visual_runs(text, Some(&ar))
+        );
+    }
+
+    #[test]
+    fn mixed_direction_sample() {
+        // Runs come out left to right as drawn so the Arabic after the Latin is first.
+        // Spaces between runs take the paragraph direction.
+        let ar = language("ar_Arab");
+        assert_eq!(
+            vec![
+                (" عالم", true, Some("Arab".to_string())),
+                ("Google Fonts", false, None),
+                ("مرحبا ", true, Some("Arab".to_string())),
+            ],
+            runs("مرحبا Google Fonts عالم", Some(&ar))
+        );
+
+        // Without a language the first strong character decides the paragraph direction
+        assert_eq!(
+            vec![("Hello ", false, None), ("עולם", true, None)],
+            runs("Hello עולם", None)
+        );
+    }
+
+    #[test]
+    fn devanagari_sample() {
+        let hi = language("hi_Deva");
+        let text = "नमस्ते दुनिया";
+        assert_eq!(
+            vec![TextRun {
+                range: 0..text.len(),
+                rtl: false,
+                script: Some("Deva".to_string()),
+                language: Some("hi".to_string()),
+            }],
+            visual_runs(text, Some(&hi))
+        );
+    }
+
+    #[test]
+    fn features_per_run() {
+        let ar = language("ar_Arab");
+        let text = "مرحبا Google Fonts عالم";
+        let features = ["smcp", "-liga[11:13]", "kern[0:2]"]
+            .iter()
+            .map(|f| f.parse::<Feature>().unwrap())
+            .collect::<Vec<_>>();
+        let latin = visual_runs(text, Some(&ar))
+            .into_iter()
+            .find(|r| !r.rtl)
+            .unwrap();
+        assert_eq!(11..23, latin.range);
+        assert_eq!(
+            vec![(*b"smcp", 1, 0, u32::MAX), (*b"liga", 0, 0, 2),],
+            latin
+                .features(&features)
+                .iter()
+                .map(|f| (f.tag.to_be_bytes(), f.value, f.start, f.end))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn empty_sample() {
+        assert!(visual_runs("", None).is_empty());
+    }
+}
@@ -1,11 +1,284 @@
-use gf_metadata::{GoogleFonts, exemplar};
-use home::home_dir;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
 
-fn main() {
-    let mut d = home_dir().expect("Must have a home dir");
-    d.push("oss/fonts");
-    //d.push("ofl/robotomono");
-    let gf = GoogleFonts::new(d, None);
+use clap::{Parser, Subcommand};
+use gf_metadata::{
+    AxisSegmentProto, Change, ExemplarPolicy, ExemplarPreference, FamilyDiff, FontFaceCss,
+    GoogleFonts, TargetTypeProto, default_cache_file, select_exemplar,
+};
+use serde_json::{Value, json};
+
+#[derive(Parser, Debug)]
+#[command(version, about, long_about = None)]
+struct Args {
+    /// Path to a google/fonts checkout. If omitted we try $GOOGLE_FONTS_DIR, the config
+    /// file, the current directory and its ancestors then ~/oss/fonts.
+    #[arg(long)]
+    repo_dir: Option<PathBuf>,
+
+    /// Reuse metadata parsed by previous runs, cached in ~/.cache/gf-metadata
+    #[arg(long)]
+    cache: bool,
+
+    /// Which font represents each family: default, static, italic, weight=N or tag=TAG.
+    /// Use the same choice for make_test_images, make_embedding and read-gf-metadata.
+    #[arg(long, default_value = "default")]
+    exemplar: ExemplarPreference,
+
+    /// Check every family against its font binaries and report the findings
+    #[arg(long)]
+    validate: bool,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Report what changed since an older google/fonts checkout and what must be regenerated
+    Diff {
+        /// Path to the older checkout
+        before: PathBuf,
+
+        /// Print JSON rather than text
+        #[arg(long)]
+        json: bool,
+
+        /// Where make_test_images wrote its png files, searched for variant images to regenerate
+        #[arg(long, default_value = "/tmp/test_png")]
+        png_dir: PathBuf,
+    },
+    /// Print @font-face CSS for families and their metric-compatible fallbacks
+    Css {
+        /// Names of the families, all families if omitted
+        families: Vec<String>,
+
+        /// Prepended to font filenames to form src urls
+        #[arg(long, default_value = "")]
+        url_prefix: String,
+
+        /// Only fallbacks for this platform: windows, mac, linux, android or ios
+        #[arg(long, value_parser = parse_target)]
+        target: Option<TargetTypeProto>,
+    },
+}
+
+fn parse_target(s: &str) -> Result<TargetTypeProto, String> {
+    match s {
+        "windows" => Ok(TargetTypeProto::TARGET_OS_WINDOWS),
+        "mac" => Ok(TargetTypeProto::TARGET_OS_MAC),
+        "linux" => Ok(TargetTypeProto::TARGET_OS_LINUX),
+        "android" => Ok(TargetTypeProto::TARGET_OS_ANDROID),
+        "ios" => Ok(TargetTypeProto::TARGET_OS_IOS),
+        _ => Err(format!(
+            "Unknown target {s:?}, expected one of windows, mac, linux, android or ios"
+        )),
+    }
+}
+
+/// The files make_test_images wrote for a family's exemplar
+///
+/// That's `{exemplar}.png` plus any variants, such as `{exemplar}-wght@400-ss01.png`, in png_dir.
+fn image_files(png_dir: &Path, exemplar: &str) -> Vec<String> {
+    let variant_prefix = format!("{exemplar}-");
+    let mut variants = fs::read_dir(png_dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|e| e.ok()?.file_name().into_string().ok())
+        .filter(|f| f.starts_with(&variant_prefix) && f.ends_with(".png"))
+        .collect::<Vec<_>>();
+    variants.sort();
+    std::iter::once(format!("{exemplar}.png"))
+        .chain(variants)
+        .collect()
+}
+
+/// The file make_embedding writes for a family
+fn embedding_file(family: &str) -> String {
+    format!("{}.pb", family.replace(" ", "_"))
+}
+
+fn axes_json(axes: &[AxisSegmentProto]) -> Value {
+    axes.iter()
+        .map(|a| json!({"tag": a.tag(), "min": a.min_value(), "max": a.max_value()}))
+        .collect()
+}
+
+fn change_json(change: &Change) -> Value {
+    match change {
+        Change::FontAdded(file) => json!({"kind": "font_added", "file": file}),
+        Change::FontRemoved(file) => json!({"kind": "font_removed", "file": file}),
+        Change::FontRenamed { from, to } => {
+            json!({"kind": "font_renamed", "from": from, "to": to})
+        }
+        Change::ExemplarChanged { from, to } => {
+            json!({"kind": "exemplar_changed", "from": from, "to": to})
+        }
+        Change::AxesChanged { from, to } => {
+            json!({"kind": "axes_changed", "from": axes_json(from), "to": axes_json(to)})
+        }
+        Change::SampleTextChanged => json!({"kind": "sample_text_changed"}),
+        Change::PrimaryLanguageChanged { from, to } => {
+            json!({"kind": "primary_language_changed", "from": from, "to": to})
+        }
+        Change::PrimaryScriptChanged { from, to } => {
+            json!({"kind": "primary_script_changed", "from": from, "to": to})
+        }
+        Change::TagChanged { tag, loc, from, to } => json!({
+            "kind": "tag_changed",
+            "tag": tag,
+            "location": loc.to_string(),
+            "from": from,
+            "to": to,
+        }),
+    }
+}
+
+fn family_json(family: &FamilyDiff) -> Value {
+    json!({
+        "name": family.name,
+        "exemplar": family.exemplar,
+        "changes": family.changes.iter().map(change_json).collect::<Vec<_>>(),
+    })
+}
+
+fn diff(
+    gf: &GoogleFonts,
+    before: PathBuf,
+    as_json: bool,
+    png_dir: &Path,
+    exemplar_policy: &dyn ExemplarPolicy,
+) -> ExitCode {
+    if !before.is_dir() {
+        eprintln!("No such directory as {before:?}");
+        return ExitCode::FAILURE;
+    }
+    let diff = gf.changes_since(&GoogleFonts::new(before, None), exemplar_policy);
+    let images = diff
+        .stale_images()
+        .into_iter()
+        .flat_map(|exemplar| image_files(png_dir, exemplar))
+        .collect::<Vec<_>>();
+    let embeddings = diff
+        .stale_embeddings()
+        .into_iter()
+        .map(embedding_file)
+        .collect::<Vec<_>>();
+
+    if as_json {
+        let families = |f: &[FamilyDiff]| f.iter().map(family_json).collect::<Vec<_>>();
+        let report = json!({
+            "added": families(&diff.added),
+            "removed": families(&diff.removed),
+            "changed": families(&diff.changed),
+            "regenerate": {
+                "images": images,
+                "embeddings": embeddings,
+            },
+        });
+        println!("{report:#}");
+    } else {
+        println!("{diff}");
+        for (what, files) in [("images", images), ("embeddings", embeddings)] {
+            if files.is_empty() {
+                continue;
+            }
+            println!("\nRegenerate {} {what}:", files.len());
+            for file in files {
+                println!("  {file}");
+            }
+        }
+    }
+    ExitCode::SUCCESS
+}
+
+fn css(
+    gf: &GoogleFonts,
+    families: &[String],
+    url_prefix: &str,
+    target: Option<TargetTypeProto>,
+) -> ExitCode {
+    let mut generator = FontFaceCss::new().url_prefix(url_prefix);
+    if let Some(target) = target {
+        generator = generator.target(target);
+    }
+    let mut code = ExitCode::SUCCESS;
+    if families.is_empty() {
+        for family in gf.families().iter().filter_map(|(_, f)| f.as_ref().ok()) {
+            print!("{}", generator.write(family));
+        }
+    }
+    for name in families {
+        match gf.family_by_name(name) {
+            Some((_, family)) => print!("{}", generator.write(family)),
+            None => {
+                eprintln!("No family named {name:?}");
+                code = ExitCode::FAILURE;
+            }
+        }
+    }
+    code
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+
+    let mut gf = match GoogleFonts::discover(args.repo_dir.as_deref(), None) {
+        Ok((gf, source)) => {
+            eprintln!("Reading {:?}, from {source}", gf.repo_dir());
+            gf
+        }
+        Err(e) => {
+            eprintln!("{e}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if let Some(cache_file) = default_cache_file().filter(|_| args.cache) {
+        gf = gf.with_cache(cache_file);
+    }
+    let exemplar_policy = match args.exemplar.policy(&gf) {
+        Ok(policy) => policy,
+        Err(e) => {
+            eprintln!("Unable to load tags for {}: {e}", args.exemplar);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if let Some(command) = args.command {
+        let code = match command {
+            Command::Diff {
+                before,
+                json,
+                png_dir,
+            } => diff(&gf, before, json, &png_dir, &*exemplar_policy),
+            Command::Css {
+                families,
+                url_prefix,
+                target,
+            } => css(&gf, &families, &url_prefix, target),
+        };
+        if let Err(e) = gf.save_cache() {
+            eprintln!("Unable to save cache: {e}");
+        }
+        return code;
+    }
+
+    if args.validate {
+        let report = gf.validate();
+        if let Err(e) = gf.save_cache() {
+            eprintln!("Unable to save cache: {e}");
+        }
+        println!("{report}");
+        return if report.has_errors() {
+            ExitCode::FAILURE
+        } else {
+            ExitCode::SUCCESS
+        };
+    }
 
     let mut metadata_success = 0;
     let mut metadata_fail = 0;
@@ -18,13 +291,13 @@ fn main() {
             metadata_fail += 1;
             continue;
         };
-        let Some(exemplar) = exemplar(&family) else {
+        let Some(exemplar) = select_exemplar(family, &*exemplar_policy) else {
             eprintln!("No exemplar for {} from {path:?}", family.name());
             metadata_fail += 1;
             continue;
         };
-        if gf.find_font_binary(&exemplar).is_none() {
-            eprintln!("No font binary for {exemplar:?} from {path:?}");
+        if let Err(e) = gf.find_font_binary(exemplar) {
+            eprintln!("{e} from {path:?}");
             metadata_fail += 1;
             continue;
         }
@@ -40,6 +313,25 @@ fn main() {
         lang_success += 1;
     }
 
+    let region_fail = gf.regions().iter().filter(|r| r.is_err()).count();
+    let script_fail = gf.scripts().iter().filter(|s| s.is_err()).count();
+    for e in gf
+        .regions()
+        .iter()
+        .filter_map(|r| r.as_ref().err())
+        .chain(gf.scripts().iter().filter_map(|s| s.as_ref().err()))
+    {
+        eprintln!("Region/script read error {e}");
+    }
+    let axis_fail = gf.axes().iter().filter(|a| a.is_err()).count();
+    for e in gf.axes().iter().filter_map(|a| a.as_ref().err()) {
+        eprintln!("Axis registry read error {e}");
+    }
+
+    if let Err(e) = gf.save_cache() {
+        eprintln!("Unable to save cache: {e}");
+    }
+
     eprintln!(
         "Read {}/{} METADATA.pb files successfully",
         metadata_success,
@@ -50,4 +342,20 @@ fn main() {
         lang_success,
         lang_success + lang_fail
     );
+    eprintln!(
+        "Read {}/{} region files successfully",
+        gf.regions().len() - region_fail,
+        gf.regions().len()
+    );
+    eprintln!(
+        "Read {}/{} script files successfully",
+        gf.scripts().len() - script_fail,
+        gf.scripts().len()
+    );
+    eprintln!(
+        "Read {}/{} axis registry files successfully",
+        gf.axes().len() - axis_fail,
+        gf.axes().len()
+    );
+    ExitCode::SUCCESS
 }
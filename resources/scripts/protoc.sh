@@ -14,6 +14,8 @@ protoc --rs_out gf-metadata/src/ --proto_path ~/oss/gftools/Lib/gftools/ ~/oss/g
 
 protoc --rs_out gf-metadata/src/ --proto_path ~/oss/fonts/lang/Lib/gflanguages/ ~/oss/fonts/lang/Lib/gflanguages/languages_public.proto
 
+protoc --rs_out gf-metadata/src/ --proto_path ~/oss/fonts/axisregistry/Lib/axisregistry/ ~/oss/fonts/axisregistry/Lib/axisregistry/axes.proto
+
 protoc --rs_out gf-embed/src/ --proto_path resources/scripts/ resources/scripts/embed_data.proto
 
 rm gf-metadata/src/mod.rs